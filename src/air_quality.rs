@@ -0,0 +1,371 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Context;
+use chrono::{DateTime, Duration, Utc};
+use reqwest::Client;
+use serde::Deserialize;
+use tracing::warn;
+
+use crate::Coordinates;
+use crate::cache::RETRY_AFTER;
+use crate::provider::base_url;
+
+/// The model runs hourly, so readings younger than this are served as is.
+const TTL: Duration = Duration::minutes(30);
+/// Readings this old are dropped rather than served as the last good ones.
+const KEEP_FOR: Duration = Duration::hours(6);
+
+/// Current pollutant and pollen readings from Open-Meteo's air-quality API,
+/// which is separate from the forecast API. Pollen is only modelled for
+/// Europe during the season, so those fields are `None` elsewhere.
+#[derive(Debug, Clone)]
+pub struct AirQuality {
+    pub european_aqi: Option<f64>,
+    pub us_aqi: Option<f64>,
+    /// Concentrations in µg/m³.
+    pub pm2_5: Option<f64>,
+    pub pm10: Option<f64>,
+    pub ozone: Option<f64>,
+    /// Grains per m³.
+    pub birch_pollen: Option<f64>,
+    pub grass_pollen: Option<f64>,
+    pub ragweed_pollen: Option<f64>,
+}
+
+impl AirQuality {
+    pub fn value(&self, reading: Reading) -> Option<f64> {
+        match reading {
+            Reading::EuropeanAqi => self.european_aqi,
+            Reading::UsAqi => self.us_aqi,
+            Reading::Pm2_5 => self.pm2_5,
+            Reading::Pm10 => self.pm10,
+            Reading::Ozone => self.ozone,
+            Reading::Birch => self.birch_pollen,
+            Reading::Grass => self.grass_pollen,
+            Reading::Ragweed => self.ragweed_pollen,
+        }
+    }
+}
+
+/// Named levels a reading is sorted into; see `Locale::air_quality_level`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Scale {
+    /// Good to extremely poor, as in the European Air Quality Index.
+    European,
+    /// Good to hazardous, as in the US EPA AQI.
+    Us,
+    /// None to very high.
+    Pollen,
+}
+
+impl Scale {
+    pub fn levels(self) -> usize {
+        match self {
+            Scale::European | Scale::Us => 6,
+            Scale::Pollen => 5,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Reading {
+    EuropeanAqi,
+    UsAqi,
+    Pm2_5,
+    Pm10,
+    Ozone,
+    Birch,
+    Grass,
+    Ragweed,
+}
+
+impl Reading {
+    pub const POLLUTANTS: [Reading; 5] = [
+        Reading::EuropeanAqi,
+        Reading::UsAqi,
+        Reading::Pm2_5,
+        Reading::Pm10,
+        Reading::Ozone,
+    ];
+    pub const POLLEN: [Reading; 3] = [Reading::Birch, Reading::Grass, Reading::Ragweed];
+
+    pub fn scale(self) -> Scale {
+        match self {
+            Reading::UsAqi => Scale::Us,
+            Reading::Birch | Reading::Grass | Reading::Ragweed => Scale::Pollen,
+            _ => Scale::European,
+        }
+    }
+
+    /// Upper bound of every level but the last. Pollutant bands follow the
+    /// EEA's European AQI; pollen bands are the US National Allergy Bureau's
+    /// for trees, grass and weeds.
+    fn thresholds(self) -> &'static [f64] {
+        match self {
+            Reading::EuropeanAqi => &[20.0, 40.0, 60.0, 80.0, 100.0],
+            Reading::UsAqi => &[50.0, 100.0, 150.0, 200.0, 300.0],
+            Reading::Pm2_5 => &[10.0, 20.0, 25.0, 50.0, 75.0],
+            Reading::Pm10 => &[20.0, 40.0, 50.0, 100.0, 150.0],
+            Reading::Ozone => &[50.0, 100.0, 130.0, 240.0, 380.0],
+            Reading::Birch => &[0.0, 14.0, 89.0, 1499.0],
+            Reading::Grass => &[0.0, 4.0, 19.0, 199.0],
+            Reading::Ragweed => &[0.0, 9.0, 49.0, 499.0],
+        }
+    }
+
+    /// Index into the reading's scale, 0 being the best level.
+    pub fn level(self, value: f64) -> usize {
+        self.thresholds()
+            .iter()
+            .filter(|threshold| value > **threshold)
+            .count()
+    }
+
+    pub fn unit(self) -> Option<&'static str> {
+        match self {
+            Reading::EuropeanAqi | Reading::UsAqi => None,
+            Reading::Pm2_5 | Reading::Pm10 | Reading::Ozone => Some("µg/m³"),
+            Reading::Birch | Reading::Grass | Reading::Ragweed => Some("/m³"),
+        }
+    }
+}
+
+struct Entry {
+    air_quality: AirQuality,
+    fetched_at: DateTime<Utc>,
+    /// Set when the last refresh failed; the entry is served as is until then.
+    retry_at: Option<DateTime<Utc>>,
+}
+
+impl Entry {
+    fn current(&self, now: DateTime<Utc>) -> Option<AirQuality> {
+        let fresh = now.signed_duration_since(self.fetched_at) < TTL;
+        let backing_off = self.retry_at.is_some_and(|retry_at| now < retry_at);
+        (fresh || backing_off).then(|| self.air_quality.clone())
+    }
+}
+
+/// Cheap to clone; clones share the cache.
+#[derive(Clone)]
+pub struct AirQualityClient {
+    http: Client,
+    base_url: String,
+    /// Keyed by coordinates rounded to two decimals, like the weather cache.
+    entries: Arc<Mutex<HashMap<(i32, i32), Entry>>>,
+}
+
+impl AirQualityClient {
+    pub fn new() -> Self {
+        Self {
+            http: Client::new(),
+            base_url: base_url(
+                "AIR_QUALITY_API_BASE_URL",
+                "https://air-quality-api.open-meteo.com",
+            ),
+            entries: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Readings for `coords`, cached for `TTL`. When a refresh fails the
+    /// last good readings are served for `RETRY_AFTER` before the next
+    /// attempt, like `WeatherCache` does.
+    pub async fn fetch(&self, coords: Coordinates) -> anyhow::Result<AirQuality> {
+        let key = cache_key(coords);
+        if let Some(air_quality) = self
+            .entries
+            .lock()
+            .unwrap()
+            .get(&key)
+            .and_then(|entry| entry.current(Utc::now()))
+        {
+            return Ok(air_quality);
+        }
+
+        let result = self.request(coords).await;
+        let now = Utc::now();
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, entry| now.signed_duration_since(entry.fetched_at) < KEEP_FOR);
+        match result {
+            Ok(air_quality) => {
+                let entry = Entry {
+                    air_quality: air_quality.clone(),
+                    fetched_at: now,
+                    retry_at: None,
+                };
+                entries.insert(key, entry);
+                Ok(air_quality)
+            }
+            Err(err) => {
+                let Some(entry) = entries.get_mut(&key) else {
+                    return Err(err);
+                };
+                warn!(
+                    ?err,
+                    "failed to refresh air quality; serving the last readings"
+                );
+                entry.retry_at = Some(now + RETRY_AFTER);
+                Ok(entry.air_quality.clone())
+            }
+        }
+    }
+
+    /// The last good readings for `coords`, for when a fetch was given up
+    /// on before it finished.
+    pub fn last(&self, coords: Coordinates) -> Option<AirQuality> {
+        let entries = self.entries.lock().unwrap();
+        Some(entries.get(&cache_key(coords))?.air_quality.clone())
+    }
+
+    async fn request(&self, coords: Coordinates) -> anyhow::Result<AirQuality> {
+        let url = format!(
+            "{}/v1/air-quality?latitude={}&longitude={}&current=european_aqi,us_aqi,pm2_5,pm10,ozone,birch_pollen,grass_pollen,ragweed_pollen",
+            self.base_url, coords.latitude, coords.longitude,
+        );
+
+        let response: AirQualityResponse = self
+            .http
+            .get(url)
+            .send()
+            .await
+            .context("failed to call air quality API")?
+            .error_for_status()
+            .context("air quality API returned an error")?
+            .json()
+            .await
+            .context("failed to decode air quality API response")?;
+
+        let current = response.current;
+        Ok(AirQuality {
+            european_aqi: current.european_aqi,
+            us_aqi: current.us_aqi,
+            pm2_5: current.pm2_5,
+            pm10: current.pm10,
+            ozone: current.ozone,
+            birch_pollen: current.birch_pollen,
+            grass_pollen: current.grass_pollen,
+            ragweed_pollen: current.ragweed_pollen,
+        })
+    }
+}
+
+fn cache_key(coords: Coordinates) -> (i32, i32) {
+    (
+        (coords.latitude * 100.0).round() as i32,
+        (coords.longitude * 100.0).round() as i32,
+    )
+}
+
+#[derive(Deserialize)]
+struct AirQualityResponse {
+    current: AirQualityCurrent,
+}
+
+/// Every field is `null` (or missing) where the model has no coverage.
+#[derive(Deserialize)]
+struct AirQualityCurrent {
+    european_aqi: Option<f64>,
+    us_aqi: Option<f64>,
+    pm2_5: Option<f64>,
+    pm10: Option<f64>,
+    ozone: Option<f64>,
+    birch_pollen: Option<f64>,
+    grass_pollen: Option<f64>,
+    ragweed_pollen: Option<f64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    use axum::Router;
+    use axum::http::StatusCode;
+    use axum::routing::get;
+
+    use super::*;
+
+    const BERLIN: Coordinates = Coordinates {
+        latitude: 52.52,
+        longitude: 13.41,
+    };
+
+    /// Outside Europe, or out of season, the pollen fields are `null`.
+    const NO_POLLEN: &str = r#"{
+        "latitude": 40.71,
+        "longitude": -74.0,
+        "current": {
+            "time": "2026-10-17T06:00",
+            "interval": 3600,
+            "european_aqi": 24,
+            "us_aqi": 38,
+            "pm2_5": 6.4,
+            "pm10": 9.1,
+            "ozone": 52.0,
+            "birch_pollen": null,
+            "grass_pollen": null,
+            "ragweed_pollen": null
+        }
+    }"#;
+
+    #[test]
+    fn null_pollen_reads_as_missing() {
+        let response: AirQualityResponse = serde_json::from_str(NO_POLLEN).unwrap();
+        let current = response.current;
+
+        assert_eq!(current.european_aqi, Some(24.0));
+        assert_eq!(current.pm2_5, Some(6.4));
+        assert_eq!(current.birch_pollen, None);
+        assert_eq!(current.grass_pollen, None);
+        assert_eq!(current.ragweed_pollen, None);
+    }
+
+    #[tokio::test]
+    async fn caches_and_falls_back_to_the_last_readings() {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let failing = Arc::new(AtomicBool::new(false));
+        let handler = {
+            let (requests, failing) = (requests.clone(), failing.clone());
+            move || async move {
+                requests.fetch_add(1, Ordering::SeqCst);
+                if failing.load(Ordering::SeqCst) {
+                    Err(StatusCode::INTERNAL_SERVER_ERROR)
+                } else {
+                    Ok(NO_POLLEN)
+                }
+            }
+        };
+        let app = Router::new().route("/v1/air-quality", get(handler));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let client = AirQualityClient {
+            http: Client::new(),
+            base_url,
+            entries: Arc::new(Mutex::new(HashMap::new())),
+        };
+        let expire = |client: &AirQualityClient| {
+            let mut entries = client.entries.lock().unwrap();
+            entries.get_mut(&cache_key(BERLIN)).unwrap().fetched_at -= TTL;
+        };
+
+        client.fetch(BERLIN).await.unwrap();
+        client.fetch(BERLIN).await.unwrap();
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        // Expired and failing: the last readings, then a back-off
+        expire(&client);
+        failing.store(true, Ordering::SeqCst);
+        let stale = client.fetch(BERLIN).await.unwrap();
+        assert_eq!(stale.us_aqi, Some(38.0));
+        client.fetch(BERLIN).await.unwrap();
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+        let elsewhere = Coordinates {
+            latitude: 40.71,
+            longitude: -74.0,
+        };
+        assert!(client.fetch(elsewhere).await.is_err());
+        assert!(client.last(elsewhere).is_none());
+    }
+}
@@ -0,0 +1,327 @@
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use reqwest::Client;
+use serde::Deserialize;
+
+use crate::Coordinates;
+use crate::provider::{USER_AGENT, base_url};
+
+mod meteoalarm;
+mod nws;
+
+use meteoalarm::MeteoAlarm;
+use nws::NwsAlerts;
+
+/// Where warnings come from (`alerts.source`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AlertSourceKind {
+    /// weather.gov active alerts for the location's point; US only.
+    Nws,
+    /// MeteoAlarm's per-country feed, narrowed down to `alerts.region`.
+    Meteoalarm,
+}
+
+/// The `[alerts]` section. Alerts are off when it is missing.
+#[derive(Clone, Debug)]
+pub struct AlertSettings {
+    pub source: AlertSourceKind,
+    /// MeteoAlarm feed name, e.g. `germany` or `united-kingdom`.
+    pub country: Option<String>,
+    /// Matched against each alert's area description, ignoring case.
+    pub region: Option<String>,
+}
+
+/// CAP severity, from least to most severe.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Unknown,
+    Minor,
+    Moderate,
+    Severe,
+    Extreme,
+}
+
+impl Severity {
+    fn from_cap(value: &str) -> Self {
+        match value.trim() {
+            "Minor" => Severity::Minor,
+            "Moderate" => Severity::Moderate,
+            "Severe" => Severity::Severe,
+            "Extreme" => Severity::Extreme,
+            _ => Severity::Unknown,
+        }
+    }
+
+    pub fn key(self) -> &'static str {
+        match self {
+            Severity::Unknown => "unknown",
+            Severity::Minor => "minor",
+            Severity::Moderate => "moderate",
+            Severity::Severe => "severe",
+            Severity::Extreme => "extreme",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Alert {
+    /// Short event name such as "Heat Advisory".
+    pub event: String,
+    pub headline: String,
+    pub severity: Severity,
+    pub expires: Option<DateTime<Utc>>,
+}
+
+pub trait AlertSource: Send + Sync {
+    fn kind(&self) -> AlertSourceKind;
+
+    /// Alerts in effect at `coords` right now, most severe first.
+    fn fetch(&self, coords: Coordinates) -> BoxFuture<'_, anyhow::Result<Vec<Alert>>>;
+}
+
+/// HTTP client and endpoints for the alert sources, overridable through the
+/// environment like the weather providers'.
+#[derive(Clone)]
+pub struct AlertSources {
+    http: Client,
+    nws_base_url: String,
+    meteoalarm_base_url: String,
+}
+
+impl AlertSources {
+    pub fn new() -> Self {
+        let http = Client::builder()
+            .user_agent(USER_AGENT)
+            .build()
+            .expect("failed to build HTTP client");
+
+        Self {
+            http,
+            nws_base_url: base_url("NWS_BASE_URL", "https://api.weather.gov"),
+            meteoalarm_base_url: base_url("METEOALARM_BASE_URL", "https://feeds.meteoalarm.org"),
+        }
+    }
+
+    pub fn get(&self, settings: &AlertSettings) -> Box<dyn AlertSource> {
+        let http = self.http.clone();
+        match settings.source {
+            AlertSourceKind::Nws => Box::new(NwsAlerts {
+                http,
+                base_url: self.nws_base_url.clone(),
+            }),
+            AlertSourceKind::Meteoalarm => Box::new(MeteoAlarm {
+                http,
+                base_url: self.meteoalarm_base_url.clone(),
+                country: settings.country.clone().unwrap_or_default(),
+                region: settings.region.clone().unwrap_or_default(),
+            }),
+        }
+    }
+}
+
+/// One `<entry>` of an Atom feed carrying CAP fields, as both NWS and
+/// MeteoAlarm publish.
+struct CapEntry {
+    title: String,
+    event: String,
+    severity: Severity,
+    status: String,
+    message_type: String,
+    area: String,
+    expires: Option<DateTime<Utc>>,
+}
+
+impl CapEntry {
+    /// Actual, not yet expired alerts that haven't been cancelled.
+    fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.status.eq_ignore_ascii_case("actual")
+            && !self.message_type.eq_ignore_ascii_case("cancel")
+            && self.expires.is_none_or(|expires| expires > now)
+    }
+
+    fn into_alert(self) -> Alert {
+        Alert {
+            headline: if self.title.is_empty() {
+                self.event.clone()
+            } else {
+                self.title
+            },
+            event: self.event,
+            severity: self.severity,
+            expires: self.expires,
+        }
+    }
+}
+
+/// Reads the CAP entries of an Atom feed. Elements are matched by local name
+/// only, as the feeds disagree on CAP namespace versions and prefixes.
+fn parse_cap_feed(xml: &str) -> anyhow::Result<Vec<CapEntry>> {
+    let document = roxmltree::Document::parse(xml)?;
+
+    Ok(document
+        .descendants()
+        .filter(|node| node.has_tag_name("entry"))
+        .map(|entry| {
+            let field = |name: &str| {
+                entry
+                    .children()
+                    .find(|child| child.tag_name().name() == name)
+                    .and_then(|child| child.text())
+                    .map(|text| text.trim().to_string())
+                    .unwrap_or_default()
+            };
+            // MeteoAlarm spells out `message_type`; a missing status is
+            // treated as a real alert
+            let message_type = match field("msgType") {
+                message_type if message_type.is_empty() => field("message_type"),
+                message_type => message_type,
+            };
+            let status = match field("status") {
+                status if status.is_empty() => "Actual".to_string(),
+                status => status,
+            };
+            CapEntry {
+                title: field("title"),
+                event: field("event"),
+                severity: Severity::from_cap(&field("severity")),
+                status,
+                message_type,
+                area: field("areaDesc"),
+                expires: DateTime::parse_from_rfc3339(&field("expires"))
+                    .ok()
+                    .map(|expires| expires.with_timezone(&Utc)),
+            }
+        })
+        .collect())
+}
+
+/// Keeps entries active at `now`, one per event (the latest expiry wins,
+/// since updates are published as new entries), most severe first.
+fn active_alerts(entries: impl IntoIterator<Item = CapEntry>, now: DateTime<Utc>) -> Vec<Alert> {
+    let mut alerts: Vec<Alert> = Vec::new();
+
+    for entry in entries.into_iter().filter(|entry| entry.is_active(now)) {
+        let alert = entry.into_alert();
+        match alerts.iter_mut().find(|known| known.event == alert.event) {
+            Some(known) if alert.expires > known.expires => *known = alert,
+            Some(_) => {}
+            None => alerts.push(alert),
+        }
+    }
+
+    alerts.sort_by(|a, b| b.severity.cmp(&a.severity).then(a.expires.cmp(&b.expires)));
+    alerts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NWS_FEED: &str = include_str!("../tests/fixtures/nws_alerts_synthetic.xml");
+
+    /// 1 PM CDT, the afternoon the fixtures were recorded.
+    pub(super) fn now() -> DateTime<Utc> {
+        "2026-10-17T18:00:00Z".parse().unwrap()
+    }
+
+    fn events(alerts: &[Alert]) -> Vec<&str> {
+        alerts.iter().map(|alert| alert.event.as_str()).collect()
+    }
+
+    #[test]
+    fn parses_nws_entries() {
+        let entries = parse_cap_feed(NWS_FEED).unwrap();
+        assert_eq!(entries.len(), 7);
+
+        let warning = &entries[2];
+        assert_eq!(warning.event, "Severe Thunderstorm Warning");
+        assert_eq!(warning.severity, Severity::Severe);
+        assert_eq!(warning.message_type, "Alert");
+        assert_eq!(warning.area, "Washington, KS");
+        assert_eq!(
+            warning.expires,
+            Some("2026-10-17T19:15:00Z".parse().unwrap())
+        );
+
+        // the special weather statement carries no status
+        assert_eq!(entries[5].status, "Actual");
+    }
+
+    #[test]
+    fn drops_tests_cancellations_and_expired_entries() {
+        let alerts = active_alerts(parse_cap_feed(NWS_FEED).unwrap(), now());
+        let events = events(&alerts);
+
+        assert!(!events.contains(&"Test Message"), "status Test");
+        assert!(!events.contains(&"Flood Watch"), "msgType Cancel");
+        assert!(!events.contains(&"Wind Advisory"), "expired at 11 AM");
+        assert!(events.contains(&"Special Weather Statement"), "no status");
+    }
+
+    #[test]
+    fn keeps_the_latest_expiry_per_event() {
+        let alerts = active_alerts(parse_cap_feed(NWS_FEED).unwrap(), now());
+        let heat: Vec<_> = alerts
+            .iter()
+            .filter(|alert| alert.event == "Heat Advisory")
+            .collect();
+
+        assert_eq!(heat.len(), 1);
+        assert_eq!(
+            heat[0].expires,
+            Some("2026-10-20T00:00:00Z".parse().unwrap())
+        );
+        assert!(heat[0].headline.contains("until October 19"));
+    }
+
+    #[test]
+    fn orders_by_severity_then_expiry() {
+        let alerts = active_alerts(parse_cap_feed(NWS_FEED).unwrap(), now());
+        assert_eq!(
+            events(&alerts),
+            [
+                "Severe Thunderstorm Warning",
+                "Heat Advisory",
+                "Special Weather Statement"
+            ]
+        );
+
+        let entry = |event: &str, severity, expires: &str| CapEntry {
+            title: String::new(),
+            event: event.to_string(),
+            severity,
+            status: "Actual".to_string(),
+            message_type: "Alert".to_string(),
+            area: String::new(),
+            expires: Some(expires.parse().unwrap()),
+        };
+        let alerts = active_alerts(
+            [
+                entry("Frost Advisory", Severity::Minor, "2026-10-18T14:00:00Z"),
+                entry("Wind Advisory", Severity::Moderate, "2026-10-18T02:00:00Z"),
+                entry(
+                    "Lake Wind Advisory",
+                    Severity::Moderate,
+                    "2026-10-17T22:00:00Z",
+                ),
+                entry(
+                    "Red Flag Warning",
+                    Severity::Unknown,
+                    "2026-10-17T23:00:00Z",
+                ),
+            ],
+            now(),
+        );
+        assert_eq!(
+            events(&alerts),
+            [
+                "Lake Wind Advisory",
+                "Wind Advisory",
+                "Frost Advisory",
+                "Red Flag Warning"
+            ]
+        );
+        assert_eq!(alerts[0].headline, "Lake Wind Advisory");
+    }
+}
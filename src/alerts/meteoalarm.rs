@@ -0,0 +1,160 @@
+use anyhow::{Context, bail};
+use chrono::Utc;
+use futures::future::BoxFuture;
+use reqwest::Client;
+
+use super::{Alert, AlertSource, AlertSourceKind, CapEntry, active_alerts, parse_cap_feed};
+use crate::Coordinates;
+
+/// MeteoAlarm's legacy Atom feed for one country. The feed isn't filtered by
+/// location, so entries are matched against the configured region instead of
+/// the coordinates.
+pub struct MeteoAlarm {
+    pub(super) http: Client,
+    pub(super) base_url: String,
+    pub(super) country: String,
+    pub(super) region: String,
+}
+
+impl AlertSource for MeteoAlarm {
+    fn kind(&self) -> AlertSourceKind {
+        AlertSourceKind::Meteoalarm
+    }
+
+    fn fetch(&self, _coords: Coordinates) -> BoxFuture<'_, anyhow::Result<Vec<Alert>>> {
+        Box::pin(self.fetch_alerts())
+    }
+}
+
+impl MeteoAlarm {
+    async fn fetch_alerts(&self) -> anyhow::Result<Vec<Alert>> {
+        if self.country.is_empty() || self.region.is_empty() {
+            bail!("MeteoAlarm needs alerts.country and alerts.region");
+        }
+        let url = format!(
+            "{}/feeds/meteoalarm-legacy-atom-{}",
+            self.base_url, self.country
+        );
+
+        let feed = self
+            .http
+            .get(url)
+            .send()
+            .await
+            .context("failed to call MeteoAlarm feed")?
+            .error_for_status()
+            .context("MeteoAlarm feed returned an error")?
+            .text()
+            .await
+            .context("failed to read MeteoAlarm feed")?;
+
+        let entries = parse_cap_feed(&feed)
+            .context("failed to parse MeteoAlarm feed")?
+            .into_iter()
+            .filter(|entry| in_region(entry, &self.region));
+        Ok(active_alerts(entries, Utc::now()))
+    }
+}
+
+/// Whether one of the areas in the entry's description (separated by `;` or
+/// `,`) names `region` as whole words, ignoring case. Hyphenated names count
+/// as one word, so "Berlin" doesn't match "Berlin-Brandenburg".
+fn in_region(entry: &CapEntry, region: &str) -> bool {
+    let words = |text: &str| -> Vec<String> {
+        text.split(|c: char| !c.is_alphanumeric() && c != '-')
+            .map(|word| word.trim_matches('-').to_lowercase())
+            .filter(|word| !word.is_empty())
+            .collect()
+    };
+    let region = words(region);
+    if region.is_empty() {
+        return false;
+    }
+    entry.area.split([';', ',']).any(|area| {
+        words(area)
+            .windows(region.len())
+            .any(|window| window == region)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alerts::Severity;
+    use crate::alerts::tests::now;
+
+    const FEED: &str = include_str!("../../tests/fixtures/meteoalarm_germany_synthetic.xml");
+
+    fn region_alerts(region: &str) -> Vec<Alert> {
+        let entries = parse_cap_feed(FEED)
+            .unwrap()
+            .into_iter()
+            .filter(|entry| in_region(entry, region));
+        active_alerts(entries, now())
+    }
+
+    #[test]
+    fn matches_region_ignoring_case() {
+        let events: Vec<_> = region_alerts("BERLIN")
+            .into_iter()
+            .map(|alert| (alert.event, alert.severity))
+            .collect();
+        assert_eq!(
+            events,
+            [
+                ("Orkanartige Böen".to_string(), Severity::Extreme),
+                ("Sturmböen".to_string(), Severity::Moderate),
+                ("Nebel".to_string(), Severity::Minor),
+            ]
+        );
+
+        let coast = region_alerts("nordfriesland");
+        assert_eq!(coast.len(), 1);
+        assert_eq!(coast[0].event, "Sturmflut");
+        assert_eq!(coast[0].headline, "Sturmflut - Kreis Nordfriesland - Küste");
+
+        assert!(region_alerts("Hamburg").is_empty());
+    }
+
+    #[test]
+    fn matches_whole_area_names() {
+        let events = |region| -> Vec<String> {
+            region_alerts(region)
+                .into_iter()
+                .map(|alert| alert.event)
+                .collect()
+        };
+
+        // "Berlin" is in neither "Berlin-Brandenburg" nor the other way round
+        assert!(!events("Berlin").contains(&"Starkregen".to_string()));
+        assert_eq!(events("berlin-brandenburg"), ["Starkregen"]);
+        assert!(events("Brandenburg").is_empty());
+        assert!(events("Berl").is_empty());
+
+        // Each area of a combined description on its own
+        assert_eq!(events("Landkreis Oder-Spree"), ["Frost"]);
+        assert_eq!(events("barnim-nord"), ["Frost"]);
+        assert!(events("Spree").is_empty());
+        assert!(events("Oder-Spree Landkreis").is_empty());
+    }
+
+    #[test]
+    fn reads_legacy_message_type() {
+        let entries = parse_cap_feed(FEED).unwrap();
+        let cancelled = entries
+            .iter()
+            .find(|entry| entry.event == "Gewitter")
+            .unwrap();
+        assert_eq!(cancelled.message_type, "Cancel");
+        assert!(!cancelled.is_active(now()));
+
+        let update = region_alerts("berlin")
+            .into_iter()
+            .find(|alert| alert.event == "Sturmböen")
+            .unwrap();
+        assert_eq!(
+            update.expires,
+            Some("2026-10-18T10:00:00Z".parse().unwrap())
+        );
+    }
+}
@@ -0,0 +1,51 @@
+use anyhow::Context;
+use chrono::Utc;
+use futures::future::BoxFuture;
+use reqwest::Client;
+use reqwest::header::ACCEPT;
+
+use super::{Alert, AlertSource, AlertSourceKind, active_alerts, parse_cap_feed};
+use crate::Coordinates;
+
+/// Active alerts from weather.gov, which filters them to the zones that
+/// contain the point.
+pub struct NwsAlerts {
+    pub(super) http: Client,
+    pub(super) base_url: String,
+}
+
+impl AlertSource for NwsAlerts {
+    fn kind(&self) -> AlertSourceKind {
+        AlertSourceKind::Nws
+    }
+
+    fn fetch(&self, coords: Coordinates) -> BoxFuture<'_, anyhow::Result<Vec<Alert>>> {
+        Box::pin(self.fetch_alerts(coords))
+    }
+}
+
+impl NwsAlerts {
+    async fn fetch_alerts(&self, coords: Coordinates) -> anyhow::Result<Vec<Alert>> {
+        let url = format!(
+            "{}/alerts/active?point={:.4},{:.4}",
+            self.base_url, coords.latitude, coords.longitude
+        );
+
+        // The Atom flavour carries the CAP fields; the default is GeoJSON
+        let feed = self
+            .http
+            .get(url)
+            .header(ACCEPT, "application/atom+xml")
+            .send()
+            .await
+            .context("failed to call NWS alerts API")?
+            .error_for_status()
+            .context("NWS alerts API returned an error")?
+            .text()
+            .await
+            .context("failed to read NWS alerts response")?;
+
+        let entries = parse_cap_feed(&feed).context("failed to parse NWS alerts feed")?;
+        Ok(active_alerts(entries, Utc::now()))
+    }
+}
@@ -0,0 +1,328 @@
+//! Sun and moon positions computed locally, so the astronomy widget and the
+//! daily sunrise/sunset keep working without the weather API.
+//!
+//! The formulas are the low-precision ones from Astronomical Algorithms (the
+//! same set suncalc uses): good to about a minute for rise and set times,
+//! which is all a wall display needs.
+
+use std::f64::consts::PI;
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+
+use crate::Coordinates;
+
+const RAD: f64 = PI / 180.0;
+/// Julian date of 2000-01-01 12:00 UTC.
+const J2000: f64 = 2_451_545.0;
+/// Obliquity of the ecliptic.
+const OBLIQUITY: f64 = 23.4397 * RAD;
+/// Sun altitude at sunrise/sunset, allowing for refraction and the disc.
+const SUNRISE_ALTITUDE: f64 = -0.833 * RAD;
+const CIVIL_TWILIGHT_ALTITUDE: f64 = -6.0 * RAD;
+/// Moon altitude at moonrise/moonset.
+const MOONRISE_ALTITUDE: f64 = 0.133 * RAD;
+/// Step when scanning a day for moonrise and moonset.
+const MOON_SCAN_MINUTES: i64 = 10;
+const SUN_DISTANCE_KM: f64 = 149_598_000.0;
+
+/// Sun events for one local calendar day. Events missing during polar day or
+/// night are `None`; `always_up` tells the two apart.
+#[derive(Debug, Clone, Copy)]
+pub struct SunTimes {
+    pub sunrise: Option<DateTime<Tz>>,
+    pub sunset: Option<DateTime<Tz>>,
+    pub civil_dawn: Option<DateTime<Tz>>,
+    pub civil_dusk: Option<DateTime<Tz>>,
+    pub always_up: bool,
+}
+
+impl SunTimes {
+    pub fn day_length(&self) -> Duration {
+        match (self.sunrise, self.sunset) {
+            (Some(rise), Some(set)) => set - rise,
+            _ if self.always_up => Duration::hours(24),
+            _ => Duration::zero(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct MoonTimes {
+    pub moonrise: Option<DateTime<Tz>>,
+    pub moonset: Option<DateTime<Tz>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct MoonPhase {
+    /// Position in the synodic month: 0 new, 0.25 first quarter, 0.5 full,
+    /// 0.75 last quarter.
+    pub phase: f64,
+    /// Illuminated fraction of the disc, 0 to 1.
+    pub illumination: f64,
+}
+
+impl MoonPhase {
+    /// One of eight named phases, 0 being new moon and 4 full moon.
+    pub fn index(&self) -> usize {
+        ((self.phase * 8.0).round() as usize) % 8
+    }
+}
+
+pub fn sun_times(coords: Coordinates, date: NaiveDate, timezone: Tz) -> SunTimes {
+    let lw = -coords.longitude * RAD;
+    let phi = coords.latitude * RAD;
+    let d = days_since_j2000(local_noon(date, timezone));
+
+    let n = (d - 0.0009 - lw / (2.0 * PI)).round();
+    let ds = 0.0009 + lw / (2.0 * PI) + n;
+    let m = solar_mean_anomaly(ds);
+    let l = ecliptic_longitude(m);
+    let dec = declination(l, 0.0);
+    let noon = J2000 + ds + 0.0053 * m.sin() - 0.0069 * (2.0 * l).sin();
+
+    // Julian dates of the morning and evening crossings of `altitude`
+    let crossings = |altitude: f64| {
+        let cos_w = (altitude.sin() - phi.sin() * dec.sin()) / (phi.cos() * dec.cos());
+        if !(-1.0..=1.0).contains(&cos_w) {
+            return None;
+        }
+        let w = cos_w.acos();
+        let ds = 0.0009 + (w + lw) / (2.0 * PI) + n;
+        let set = J2000 + ds + 0.0053 * m.sin() - 0.0069 * (2.0 * l).sin();
+        Some((noon - (set - noon), set))
+    };
+    let to_local = |julian: f64| from_julian(julian).with_timezone(&timezone);
+
+    let sun = crossings(SUNRISE_ALTITUDE);
+    let civil = crossings(CIVIL_TWILIGHT_ALTITUDE);
+    SunTimes {
+        sunrise: sun.map(|(rise, _)| to_local(rise)),
+        sunset: sun.map(|(_, set)| to_local(set)),
+        civil_dawn: civil.map(|(dawn, _)| to_local(dawn)),
+        civil_dusk: civil.map(|(_, dusk)| to_local(dusk)),
+        always_up: sun.is_none() && (phi - dec).abs() < PI / 2.0 - SUNRISE_ALTITUDE,
+    }
+}
+
+/// Moonrise and moonset during the local calendar day, found by scanning the
+/// moon's altitude; either can be missing on a given day.
+pub fn moon_times(coords: Coordinates, date: NaiveDate, timezone: Tz) -> MoonTimes {
+    let start = local_midnight(date, timezone);
+    let end = local_midnight(date + Duration::days(1), timezone);
+    let altitude = |at: DateTime<Utc>| moon_altitude(coords, at) - MOONRISE_ALTITUDE;
+
+    let mut times = MoonTimes {
+        moonrise: None,
+        moonset: None,
+    };
+    let mut previous = (start, altitude(start));
+    let mut at = start;
+    while at < end {
+        at = (at + Duration::minutes(MOON_SCAN_MINUTES)).min(end);
+        let current = (at, altitude(at));
+        if previous.1.signum() != current.1.signum() {
+            // Interpolate linearly between the two samples
+            let fraction = previous.1 / (previous.1 - current.1);
+            let step = (current.0 - previous.0).num_seconds() as f64;
+            let crossing = previous.0 + Duration::seconds((fraction * step).round() as i64);
+            let crossing = Some(crossing.with_timezone(&timezone));
+            if current.1 > 0.0 {
+                times.moonrise = times.moonrise.or(crossing);
+            } else {
+                times.moonset = times.moonset.or(crossing);
+            }
+        }
+        previous = current;
+    }
+    times
+}
+
+pub fn moon_phase(at: DateTime<Utc>) -> MoonPhase {
+    let d = days_since_j2000(at);
+    let (sun_ra, sun_dec) = sun_coordinates(d);
+    let (moon_ra, moon_dec, moon_distance) = moon_coordinates(d);
+
+    let elongation = (sun_dec.sin() * moon_dec.sin()
+        + sun_dec.cos() * moon_dec.cos() * (sun_ra - moon_ra).cos())
+    .acos();
+    let inclination = (SUN_DISTANCE_KM * elongation.sin())
+        .atan2(moon_distance - SUN_DISTANCE_KM * elongation.cos());
+    let angle = (sun_dec.cos() * (sun_ra - moon_ra).sin()).atan2(
+        sun_dec.sin() * moon_dec.cos() - sun_dec.cos() * moon_dec.sin() * (sun_ra - moon_ra).cos(),
+    );
+
+    MoonPhase {
+        phase: 0.5 + 0.5 * inclination * angle.signum() / PI,
+        illumination: (1.0 + inclination.cos()) / 2.0,
+    }
+}
+
+fn moon_altitude(coords: Coordinates, at: DateTime<Utc>) -> f64 {
+    let d = days_since_j2000(at);
+    let lw = -coords.longitude * RAD;
+    let phi = coords.latitude * RAD;
+    let (ra, dec, _) = moon_coordinates(d);
+    let hour_angle = sidereal_time(d, lw) - ra;
+
+    (phi.sin() * dec.sin() + phi.cos() * dec.cos() * hour_angle.cos()).asin()
+}
+
+/// Right ascension and declination of the sun.
+fn sun_coordinates(d: f64) -> (f64, f64) {
+    let l = ecliptic_longitude(solar_mean_anomaly(d));
+    (right_ascension(l, 0.0), declination(l, 0.0))
+}
+
+/// Right ascension, declination and distance in km of the moon.
+fn moon_coordinates(d: f64) -> (f64, f64, f64) {
+    let mean_longitude = (218.316 + 13.176_396 * d) * RAD;
+    let mean_anomaly = (134.963 + 13.064_993 * d) * RAD;
+    let argument_of_latitude = (93.272 + 13.229_350 * d) * RAD;
+
+    let l = mean_longitude + 6.289 * RAD * mean_anomaly.sin();
+    let b = 5.128 * RAD * argument_of_latitude.sin();
+    let distance = 385_001.0 - 20_905.0 * mean_anomaly.cos();
+
+    (right_ascension(l, b), declination(l, b), distance)
+}
+
+fn solar_mean_anomaly(d: f64) -> f64 {
+    (357.5291 + 0.985_600_28 * d) * RAD
+}
+
+fn ecliptic_longitude(m: f64) -> f64 {
+    let center = (1.9148 * m.sin() + 0.02 * (2.0 * m).sin() + 0.0003 * (3.0 * m).sin()) * RAD;
+    let perihelion = 102.9372 * RAD;
+    m + center + perihelion + PI
+}
+
+fn right_ascension(l: f64, b: f64) -> f64 {
+    (l.sin() * OBLIQUITY.cos() - b.tan() * OBLIQUITY.sin()).atan2(l.cos())
+}
+
+fn declination(l: f64, b: f64) -> f64 {
+    (b.sin() * OBLIQUITY.cos() + b.cos() * OBLIQUITY.sin() * l.sin()).asin()
+}
+
+fn sidereal_time(d: f64, lw: f64) -> f64 {
+    (280.16 + 360.985_623_5 * d) * RAD - lw
+}
+
+fn days_since_j2000(at: DateTime<Utc>) -> f64 {
+    at.timestamp() as f64 / 86_400.0 + 2_440_587.5 - J2000
+}
+
+fn from_julian(julian: f64) -> DateTime<Utc> {
+    let secs = ((julian - 2_440_587.5) * 86_400.0).round() as i64;
+    DateTime::from_timestamp(secs, 0).unwrap_or_default()
+}
+
+fn local_noon(date: NaiveDate, timezone: Tz) -> DateTime<Utc> {
+    local_time(
+        date,
+        NaiveTime::from_hms_opt(12, 0, 0).unwrap_or_default(),
+        timezone,
+    )
+}
+
+fn local_midnight(date: NaiveDate, timezone: Tz) -> DateTime<Utc> {
+    local_time(date, NaiveTime::MIN, timezone)
+}
+
+/// Falls back to the wall-clock reading as UTC when a DST gap skips `time`.
+fn local_time(date: NaiveDate, time: NaiveTime, timezone: Tz) -> DateTime<Utc> {
+    let naive = date.and_time(time);
+    timezone
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(|| naive.and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Timelike;
+    use chrono_tz::Europe;
+
+    use super::*;
+
+    const BERLIN: Coordinates = Coordinates {
+        latitude: 52.52,
+        longitude: 13.405,
+    };
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    /// Asserts `time` is within two minutes of `hour:minute` local time.
+    fn assert_near(time: Option<DateTime<Tz>>, hour: u32, minute: u32) {
+        let time = time.expect("event missing");
+        let expected = time.date_naive().and_hms_opt(hour, minute, 0).unwrap();
+        let off = (time.naive_local() - expected).num_seconds().abs();
+        assert!(off <= 120, "{time} is not near {hour:02}:{minute:02}");
+    }
+
+    #[test]
+    fn berlin_solstice_matches_published_times() {
+        // Published: sunrise 04:43, sunset 21:33 CEST
+        let sun = sun_times(BERLIN, date(2026, 6, 21), Europe::Berlin);
+
+        assert_near(sun.sunrise, 4, 43);
+        assert_near(sun.sunset, 21, 33);
+        assert!(!sun.always_up);
+        assert_eq!(sun.day_length().num_minutes(), 16 * 60 + 50);
+    }
+
+    #[test]
+    fn no_sunrise_in_the_polar_night() {
+        let tromso = Coordinates {
+            latitude: 69.65,
+            longitude: 18.96,
+        };
+        let sun = sun_times(tromso, date(2026, 12, 21), Europe::Oslo);
+
+        assert!(sun.sunrise.is_none() && sun.sunset.is_none());
+        assert!(!sun.always_up);
+        assert_eq!(sun.day_length(), Duration::zero());
+        // The sun still gets close enough to the horizon for civil twilight
+        assert!(sun.civil_dawn.is_some() && sun.civil_dusk.is_some());
+    }
+
+    #[test]
+    fn known_full_and_new_moons() {
+        // Full moon 2024-03-25 07:00 UTC, new moon (total eclipse) 2024-04-08 18:21 UTC
+        let full = moon_phase("2024-03-25T07:00:00Z".parse().unwrap());
+        assert_eq!(full.index(), 4);
+        assert!(full.illumination > 0.99);
+        assert!((full.phase - 0.5).abs() < 0.01);
+
+        let new = moon_phase("2024-04-08T18:21:00Z".parse().unwrap());
+        assert_eq!(new.index(), 0);
+        assert!(new.illumination < 0.01);
+    }
+
+    #[test]
+    fn phase_index_wraps_to_new_moon() {
+        let phase = |phase| MoonPhase {
+            phase,
+            illumination: 0.0,
+        };
+        assert_eq!(phase(0.25).index(), 2);
+        assert_eq!(phase(0.74).index(), 6);
+        assert_eq!(phase(0.97).index(), 0);
+    }
+
+    #[test]
+    fn no_moonrise_when_it_slips_past_midnight() {
+        // Just before last quarter the moon rises around midnight: late on
+        // the 8th, then not until after midnight on the 10th
+        let moon = |day| moon_times(BERLIN, date(2026, 1, day), Europe::Berlin);
+
+        assert!(moon(9).moonrise.is_none());
+        assert!(moon(9).moonset.is_some());
+        assert!(moon(8).moonrise.unwrap().hour() >= 22);
+        assert_eq!(moon(10).moonrise.unwrap().hour(), 0);
+    }
+}
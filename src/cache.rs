@@ -0,0 +1,355 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Duration, Utc};
+use tracing::warn;
+
+use crate::provider::{ProviderKind, WeatherProvider};
+use crate::{Coordinates, WeatherData, WeatherQuery};
+
+/// How long a stale entry is served after a failed refresh before the
+/// upstream is asked again, so a dead API isn't hit on every render.
+pub(crate) const RETRY_AFTER: Duration = Duration::minutes(1);
+/// Entries not refreshed for this long are dropped, so coordinates asked for
+/// once don't stay in memory. Data this old isn't worth serving as stale.
+const KEEP_FOR: Duration = Duration::hours(24);
+
+/// Provider and coordinates rounded to two decimals (~1 km), so nearby
+/// requests share an entry, plus the query since it changes what comes back.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct CacheKey {
+    provider: ProviderKind,
+    latitude: i32,
+    longitude: i32,
+    query: WeatherQuery,
+}
+
+impl CacheKey {
+    fn new(provider: ProviderKind, coords: Coordinates, query: WeatherQuery) -> Self {
+        Self {
+            provider,
+            latitude: (coords.latitude * 100.0).round() as i32,
+            longitude: (coords.longitude * 100.0).round() as i32,
+            query,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CachedWeather {
+    pub data: WeatherData,
+    pub fetched_at: DateTime<Utc>,
+    /// Set when the upstream fetch failed and this is the last good response.
+    pub stale: bool,
+}
+
+struct Entry {
+    weather: CachedWeather,
+    /// Set when the last refresh failed; the entry is served as is until then.
+    retry_at: Option<DateTime<Utc>>,
+}
+
+impl Entry {
+    /// The cached value if it can be served without asking the upstream.
+    fn current(&self, ttl: Duration) -> Option<CachedWeather> {
+        let now = Utc::now();
+        if now.signed_duration_since(self.weather.fetched_at) < ttl {
+            return Some(self.weather.clone());
+        }
+        match self.retry_at {
+            Some(retry_at) if now < retry_at => Some(CachedWeather {
+                stale: true,
+                ..self.weather.clone()
+            }),
+            _ => None,
+        }
+    }
+}
+
+pub struct WeatherCache {
+    entries: Mutex<HashMap<CacheKey, Entry>>,
+    /// Held while a key is refreshed, so concurrent misses wait for one
+    /// upstream request instead of each sending their own.
+    refreshing: Mutex<HashMap<CacheKey, Arc<tokio::sync::Mutex<()>>>>,
+}
+
+impl WeatherCache {
+    pub fn new() -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            refreshing: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns cached weather for `coords` while it is younger than `ttl`,
+    /// otherwise refetches. When the refetch fails the last good value is
+    /// served with `stale` set, and keeps being served for `RETRY_AFTER`
+    /// before the next attempt; the error is only returned if nothing was
+    /// ever cached for this location.
+    pub async fn fetch(
+        &self,
+        provider: &dyn WeatherProvider,
+        coords: Coordinates,
+        query: WeatherQuery,
+        ttl: Duration,
+    ) -> anyhow::Result<CachedWeather> {
+        let key = CacheKey::new(provider.kind(), coords, query);
+        if let Some(weather) = self.current(&key, ttl) {
+            return Ok(weather);
+        }
+
+        let lock = self
+            .refreshing
+            .lock()
+            .unwrap()
+            .entry(key)
+            .or_default()
+            .clone();
+        let _guard = lock.lock().await;
+        // Whoever held the lock may have just refreshed the entry
+        if let Some(weather) = self.current(&key, ttl) {
+            return Ok(weather);
+        }
+
+        let result = self.refresh(provider, key, coords, query).await;
+        // Waiters keep their own handle to the lock and find the entry
+        // refreshed once they get it
+        self.refreshing.lock().unwrap().remove(&key);
+        result
+    }
+
+    async fn refresh(
+        &self,
+        provider: &dyn WeatherProvider,
+        key: CacheKey,
+        coords: Coordinates,
+        query: WeatherQuery,
+    ) -> anyhow::Result<CachedWeather> {
+        match provider.fetch(coords, query).await {
+            Ok(data) => {
+                let now = Utc::now();
+                let weather = CachedWeather {
+                    data,
+                    fetched_at: now,
+                    stale: false,
+                };
+                let entry = Entry {
+                    weather: weather.clone(),
+                    retry_at: None,
+                };
+                let mut entries = self.entries.lock().unwrap();
+                entries.retain(|_, entry| {
+                    now.signed_duration_since(entry.weather.fetched_at) < KEEP_FOR
+                });
+                entries.insert(key, entry);
+                Ok(weather)
+            }
+            Err(err) => {
+                let mut entries = self.entries.lock().unwrap();
+                let Some(entry) = entries.get_mut(&key) else {
+                    return Err(err);
+                };
+                warn!(?err, "failed to refresh weather; serving stale cache entry");
+                entry.retry_at = Some(Utc::now() + RETRY_AFTER);
+                Ok(CachedWeather {
+                    stale: true,
+                    ..entry.weather.clone()
+                })
+            }
+        }
+    }
+
+    fn current(&self, key: &CacheKey, ttl: Duration) -> Option<CachedWeather> {
+        self.entries.lock().unwrap().get(key)?.current(ttl)
+    }
+
+    /// The last good response for `coords`, marked stale, for when a fetch
+    /// was given up on before it finished.
+    pub fn last(
+        &self,
+        provider: &dyn WeatherProvider,
+        coords: Coordinates,
+        query: WeatherQuery,
+    ) -> Option<CachedWeather> {
+        let key = CacheKey::new(provider.kind(), coords, query);
+        let entry = self.entries.lock().unwrap().get(&key)?.weather.clone();
+        Some(CachedWeather {
+            stale: true,
+            ..entry
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    use axum::Router;
+    use axum::http::StatusCode;
+    use axum::routing::get;
+
+    use super::*;
+    use crate::provider;
+    use crate::units::Units;
+
+    const QUERY: WeatherQuery = WeatherQuery {
+        units: Units::Metric,
+        forecast_days: 3,
+    };
+    const BERLIN: Coordinates = Coordinates {
+        latitude: 52.52,
+        longitude: 13.41,
+    };
+
+    /// Local stand-in for Open-Meteo answering with a recorded response,
+    /// or a 500 while `failing` is set.
+    #[derive(Default)]
+    struct MockApi {
+        requests: AtomicUsize,
+        failing: AtomicBool,
+    }
+
+    impl MockApi {
+        async fn start() -> (Arc<Self>, Box<dyn WeatherProvider>) {
+            let api = Arc::new(Self::default());
+            let handler = {
+                let api = api.clone();
+                move || async move {
+                    api.requests.fetch_add(1, Ordering::SeqCst);
+                    // Long enough for concurrent misses to pile up
+                    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+                    if api.failing.load(Ordering::SeqCst) {
+                        Err(StatusCode::INTERNAL_SERVER_ERROR)
+                    } else {
+                        Ok(include_str!("../tests/fixtures/open_meteo.json"))
+                    }
+                }
+            };
+            let app = Router::new().route("/v1/forecast", get(handler));
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let url = format!("http://{}", listener.local_addr().unwrap());
+            tokio::spawn(async move { axum::serve(listener, app).await });
+            (api, provider::open_meteo(&url))
+        }
+
+        fn requests(&self) -> usize {
+            self.requests.load(Ordering::SeqCst)
+        }
+    }
+
+    #[tokio::test]
+    async fn serves_hits_within_the_ttl() {
+        let (api, provider) = MockApi::start().await;
+        let cache = WeatherCache::new();
+        let ttl = Duration::minutes(15);
+
+        let first = cache.fetch(&*provider, BERLIN, QUERY, ttl).await.unwrap();
+        let second = cache.fetch(&*provider, BERLIN, QUERY, ttl).await.unwrap();
+
+        assert_eq!(api.requests(), 1);
+        assert_eq!(first.fetched_at, second.fetched_at);
+        assert!(!second.stale);
+        assert_eq!(second.data.snapshot.temperature, 12.6);
+    }
+
+    #[tokio::test]
+    async fn refreshes_after_the_ttl() {
+        let (api, provider) = MockApi::start().await;
+        let cache = WeatherCache::new();
+
+        let first = cache
+            .fetch(&*provider, BERLIN, QUERY, Duration::zero())
+            .await
+            .unwrap();
+        let second = cache
+            .fetch(&*provider, BERLIN, QUERY, Duration::zero())
+            .await
+            .unwrap();
+
+        assert_eq!(api.requests(), 2);
+        assert!(second.fetched_at > first.fetched_at);
+        assert!(!second.stale);
+    }
+
+    #[tokio::test]
+    async fn serves_stale_data_when_the_refresh_fails() {
+        let (api, provider) = MockApi::start().await;
+        let cache = WeatherCache::new();
+        let good = cache
+            .fetch(&*provider, BERLIN, QUERY, Duration::zero())
+            .await
+            .unwrap();
+
+        api.failing.store(true, Ordering::SeqCst);
+        let stale = cache
+            .fetch(&*provider, BERLIN, QUERY, Duration::zero())
+            .await
+            .unwrap();
+        assert!(stale.stale);
+        assert_eq!(stale.fetched_at, good.fetched_at);
+        assert_eq!(api.requests(), 2);
+
+        // Backs off instead of asking the failing API again
+        let again = cache
+            .fetch(&*provider, BERLIN, QUERY, Duration::zero())
+            .await
+            .unwrap();
+        assert!(again.stale);
+        assert_eq!(api.requests(), 2);
+    }
+
+    #[tokio::test]
+    async fn fails_when_nothing_is_cached() {
+        let (api, provider) = MockApi::start().await;
+        api.failing.store(true, Ordering::SeqCst);
+        let cache = WeatherCache::new();
+
+        let result = cache
+            .fetch(&*provider, BERLIN, QUERY, Duration::minutes(15))
+            .await;
+
+        assert!(result.is_err());
+        assert!(cache.last(&*provider, BERLIN, QUERY).is_none());
+    }
+
+    #[tokio::test]
+    async fn concurrent_misses_share_one_request() {
+        let (api, provider) = MockApi::start().await;
+        let cache = WeatherCache::new();
+        let ttl = Duration::minutes(15);
+
+        let results =
+            futures::future::join_all((0..4).map(|_| cache.fetch(&*provider, BERLIN, QUERY, ttl)))
+                .await;
+
+        assert!(results.iter().all(Result::is_ok));
+        assert_eq!(api.requests(), 1);
+        assert!(cache.refreshing.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn drops_entries_not_refreshed_for_a_day() {
+        let (_api, provider) = MockApi::start().await;
+        let cache = WeatherCache::new();
+        let ttl = Duration::minutes(15);
+        let oslo = Coordinates {
+            latitude: 59.91,
+            longitude: 10.75,
+        };
+
+        cache.fetch(&*provider, BERLIN, QUERY, ttl).await.unwrap();
+        let berlin = CacheKey::new(provider.kind(), BERLIN, QUERY);
+        cache
+            .entries
+            .lock()
+            .unwrap()
+            .get_mut(&berlin)
+            .unwrap()
+            .weather
+            .fetched_at -= KEEP_FOR;
+
+        cache.fetch(&*provider, oslo, QUERY, ttl).await.unwrap();
+        assert!(cache.last(&*provider, BERLIN, QUERY).is_none());
+        assert!(cache.last(&*provider, oslo, QUERY).is_some());
+    }
+}
@@ -0,0 +1,151 @@
+/// Weather condition shared by all providers. Each adapter maps its own codes
+/// (WMO, MET Norway symbols, OpenWeatherMap ids, NWS icons) onto this; the
+/// variants follow the WMO interpretation codes Open-Meteo reports.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WeatherCondition {
+    Clear,
+    MainlyClear,
+    PartlyCloudy,
+    Overcast,
+    Fog,
+    RimeFog,
+    Drizzle(Intensity),
+    FreezingDrizzle(Intensity),
+    Rain(Intensity),
+    FreezingRain(Intensity),
+    Snowfall(Intensity),
+    SnowGrains,
+    RainShowers(Intensity),
+    SnowShowers(Intensity),
+    Thunderstorm,
+    ThunderstormWithHail(Intensity),
+    #[default]
+    Unknown,
+}
+
+/// WMO's slight/light, moderate and heavy/dense/violent grades.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Intensity {
+    Light,
+    Moderate,
+    Heavy,
+}
+
+impl WeatherCondition {
+    /// Maps a WMO weather interpretation code as used by Open-Meteo.
+    pub fn from_wmo(code: i32) -> Self {
+        use Intensity::*;
+
+        match code {
+            0 => Self::Clear,
+            1 => Self::MainlyClear,
+            2 => Self::PartlyCloudy,
+            3 => Self::Overcast,
+            45 => Self::Fog,
+            48 => Self::RimeFog,
+            51 => Self::Drizzle(Light),
+            53 => Self::Drizzle(Moderate),
+            55 => Self::Drizzle(Heavy),
+            56 => Self::FreezingDrizzle(Light),
+            57 => Self::FreezingDrizzle(Heavy),
+            61 => Self::Rain(Light),
+            63 => Self::Rain(Moderate),
+            65 => Self::Rain(Heavy),
+            66 => Self::FreezingRain(Light),
+            67 => Self::FreezingRain(Heavy),
+            71 => Self::Snowfall(Light),
+            73 => Self::Snowfall(Moderate),
+            75 => Self::Snowfall(Heavy),
+            77 => Self::SnowGrains,
+            80 => Self::RainShowers(Light),
+            81 => Self::RainShowers(Moderate),
+            82 => Self::RainShowers(Heavy),
+            85 => Self::SnowShowers(Light),
+            86 => Self::SnowShowers(Heavy),
+            95 => Self::Thunderstorm,
+            96 => Self::ThunderstormWithHail(Light),
+            99 => Self::ThunderstormWithHail(Heavy),
+            _ => Self::Unknown,
+        }
+    }
+
+    fn intensity(self) -> Option<Intensity> {
+        match self {
+            Self::Drizzle(intensity)
+            | Self::FreezingDrizzle(intensity)
+            | Self::Rain(intensity)
+            | Self::FreezingRain(intensity)
+            | Self::Snowfall(intensity)
+            | Self::RainShowers(intensity)
+            | Self::SnowShowers(intensity)
+            | Self::ThunderstormWithHail(intensity) => Some(intensity),
+            _ => None,
+        }
+    }
+
+    /// Stable key the templates pick an icon by, e.g. `clear-night` or
+    /// `rain-showers-day-heavy`. Segments go from general to specific so a
+    /// template can drop trailing ones until it finds an icon it has.
+    pub fn icon_key(self, is_day: bool) -> String {
+        let (base, varies_by_daylight) = match self {
+            Self::Clear => ("clear", true),
+            Self::MainlyClear => ("mainly-clear", true),
+            Self::PartlyCloudy => ("partly-cloudy", true),
+            Self::Overcast => ("overcast", false),
+            Self::Fog => ("fog", false),
+            Self::RimeFog => ("rime-fog", false),
+            Self::Drizzle(_) => ("drizzle", false),
+            Self::FreezingDrizzle(_) => ("freezing-drizzle", false),
+            Self::Rain(_) => ("rain", false),
+            Self::FreezingRain(_) => ("freezing-rain", false),
+            Self::Snowfall(_) => ("snow", false),
+            Self::SnowGrains => ("snow-grains", false),
+            Self::RainShowers(_) => ("rain-showers", true),
+            Self::SnowShowers(_) => ("snow-showers", true),
+            Self::Thunderstorm => ("thunderstorm", false),
+            Self::ThunderstormWithHail(_) => ("thunderstorm-hail", false),
+            Self::Unknown => ("unknown", false),
+        };
+
+        let mut key = base.to_string();
+        if varies_by_daylight {
+            key.push_str(if is_day { "-day" } else { "-night" });
+        }
+        match self.intensity() {
+            Some(Intensity::Light) => key.push_str("-light"),
+            Some(Intensity::Heavy) => key.push_str("-heavy"),
+            Some(Intensity::Moderate) | None => {}
+        }
+        key
+    }
+
+    /// Rough ranking used to pick one condition for a whole day out of
+    /// hourly samples: a day with an afternoon thunderstorm shows the storm.
+    pub fn severity(self) -> u8 {
+        let base = match self {
+            Self::Unknown => 0,
+            Self::Clear => 1,
+            Self::MainlyClear => 2,
+            Self::PartlyCloudy => 3,
+            Self::Overcast => 4,
+            Self::Fog => 5,
+            Self::RimeFog => 6,
+            Self::Drizzle(_) => 7,
+            Self::RainShowers(_) => 8,
+            Self::Rain(_) => 9,
+            Self::SnowGrains => 10,
+            Self::SnowShowers(_) => 11,
+            Self::Snowfall(_) => 12,
+            Self::FreezingDrizzle(_) => 13,
+            Self::FreezingRain(_) => 14,
+            Self::Thunderstorm => 15,
+            Self::ThunderstormWithHail(_) => 16,
+        };
+        let grade = match self.intensity() {
+            Some(Intensity::Light) => 0,
+            Some(Intensity::Moderate) | None => 1,
+            Some(Intensity::Heavy) => 2,
+        };
+        base * 3 + grade
+    }
+}
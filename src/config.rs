@@ -0,0 +1,997 @@
+use std::{
+    collections::HashMap,
+    env, fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time,
+};
+
+use anyhow::{Context, bail};
+use chrono::Duration;
+use chrono_tz::Tz;
+use serde::Deserialize;
+
+use crate::alerts::{AlertSettings, AlertSourceKind};
+use crate::device::{DEFAULT_DEVICE, DeviceEntry, DeviceProfile, validate_dimension};
+use crate::geocoding::{GeocoderKind, GeocodingSettings};
+use crate::locale::Locale;
+use crate::prerender::PrerenderSettings;
+use crate::provider::ProviderKind;
+use crate::units::Units;
+use crate::{Coordinates, CoordinatesPath, RenderParams, RenderTarget};
+
+const DEFAULT_WEATHER_CACHE_TTL_SECS: u64 = 15 * 60;
+const DEFAULT_SOURCE_TIMEOUT_SECS: u64 = 8;
+const DEFAULT_RENDER_DEADLINE_SECS: u64 = 20;
+const DEFAULT_PRERENDER_INTERVAL_SECS: u64 = 5 * 60;
+/// Shortest pre-render cadence; renders take seconds on small boards.
+const MIN_PRERENDER_INTERVAL_SECS: u64 = 30;
+const DEFAULT_DAILY_DAYS: u8 = 5;
+const DEFAULT_HOURLY_INTERVAL_HOURS: u32 = 3;
+const DEFAULT_HOURLY_COUNT: usize = 4;
+const DEFAULT_HOURLY_HORIZON_HOURS: u32 = 24;
+const DEFAULT_CHART_HOURS: u32 = 24;
+/// Longest forecast Open-Meteo serves.
+const MAX_FORECAST_DAYS: u8 = 16;
+const DEFAULT_CONFIG_PATH: &str = "dashboard.toml";
+const DEFAULT_GEOCODING_CACHE_PATH: &str = "geocoding-cache.toml";
+/// Widest grid the layout can be split into.
+const MAX_LAYOUT_COLUMNS: u8 = 4;
+/// Compact cards that fit beside each other under the primary weather card.
+const MAX_SECONDARY_LOCATIONS: usize = 4;
+
+#[derive(Clone, Debug)]
+pub struct DashboardConfig {
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Weather backend for the location.
+    pub provider: ProviderKind,
+    /// Profile used when a request doesn't name a device.
+    pub display: DeviceProfile,
+    pub devices: HashMap<String, DeviceProfile>,
+    pub units: Units,
+    pub locale: Locale,
+    pub timezone: Option<Tz>,
+    pub icons: IconTheme,
+    pub layout: Layout,
+    /// Chips shown under the current conditions, in order.
+    pub metrics: Vec<Metric>,
+    /// Number of days shown by the daily forecast widget (3 to 7).
+    pub daily_days: u8,
+    pub hourly: HourlySampling,
+    /// Hours covered by the temperature/precipitation chart (24 to 48).
+    pub chart_hours: u32,
+    /// Compare today with yesterday and the climate normal in the weather
+    /// widget.
+    pub compare_history: bool,
+    pub wanikani_token: Option<String>,
+    pub openweathermap_key: Option<String>,
+    /// Warning banner source; `None` leaves alerts off.
+    pub alerts: Option<AlertSettings>,
+    /// Multi-location dashboards by ID, served at `/dashboard/{id}`.
+    pub dashboards: HashMap<String, LocationSet>,
+    /// Name lookups for `/render/place/{name}`.
+    pub geocoding: GeocodingSettings,
+    pub cache_ttl: Duration,
+    /// Longest wait for any one upstream source.
+    pub source_timeout: time::Duration,
+    /// Longest wait for all of a render's data together; sources still
+    /// outstanding then are drawn as unavailable.
+    pub render_deadline: time::Duration,
+    /// Dashboards kept rendered in the background.
+    pub prerender: Vec<PrerenderSettings>,
+}
+
+/// A place with a label, as listed in `[dashboards.<id>]`.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NamedLocation {
+    pub name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl NamedLocation {
+    pub fn coordinates(&self) -> Coordinates {
+        Coordinates {
+            latitude: self.latitude,
+            longitude: self.longitude,
+        }
+    }
+}
+
+/// One `[dashboards.<id>]` section: the place that gets the full set of
+/// widgets, plus places shown as compact cards under its weather card.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LocationSet {
+    pub primary: NamedLocation,
+    #[serde(default)]
+    pub secondary: Vec<NamedLocation>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WidgetKind {
+    Weather,
+    /// Compact cards for the secondary places of a `[dashboards]` entry.
+    Locations,
+    Daily,
+    Chart,
+    Astronomy,
+    #[serde(rename = "air-quality")]
+    AirQuality,
+    Wanikani,
+}
+
+impl WidgetKind {
+    pub fn key(self) -> &'static str {
+        match self {
+            WidgetKind::Weather => "weather",
+            WidgetKind::Locations => "locations",
+            WidgetKind::Daily => "daily",
+            WidgetKind::Chart => "chart",
+            WidgetKind::Astronomy => "astronomy",
+            WidgetKind::AirQuality => "air-quality",
+            WidgetKind::Wanikani => "wanikani",
+        }
+    }
+
+    /// Fewest of `columns` the widget can span before its template clips:
+    /// the chart, astronomy and air-quality cards fit in half the width,
+    /// the others need all of it.
+    pub fn min_span(self, columns: u8) -> u8 {
+        match self {
+            WidgetKind::Chart | WidgetKind::Astronomy | WidgetKind::AirQuality => {
+                columns.div_ceil(2)
+            }
+            WidgetKind::Weather
+            | WidgetKind::Locations
+            | WidgetKind::Daily
+            | WidgetKind::Wanikani => columns,
+        }
+    }
+}
+
+/// Which widgets the dashboard shows, top to bottom, on a grid of `columns`
+/// equal columns (`widgets.columns` and `widgets.enabled`).
+#[derive(Clone, Debug)]
+pub struct Layout {
+    pub columns: u8,
+    pub slots: Vec<WidgetSlot>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WidgetSlot {
+    pub widget: WidgetKind,
+    /// Columns the widget spans; widgets flow left to right and wrap.
+    pub span: u8,
+}
+
+impl Layout {
+    /// The configured slots, plus a full-width `locations` slot after the
+    /// weather widget (or first, without one) when the dashboard has
+    /// secondary places but the config doesn't place their cards itself.
+    pub fn slots(&self, has_locations: bool) -> Vec<WidgetSlot> {
+        let mut slots = self.slots.clone();
+        if has_locations && !self.contains(WidgetKind::Locations) {
+            let position = slots
+                .iter()
+                .position(|slot| slot.widget == WidgetKind::Weather)
+                .map_or(0, |index| index + 1);
+            slots.insert(
+                position,
+                WidgetSlot {
+                    widget: WidgetKind::Locations,
+                    span: self.columns,
+                },
+            );
+        }
+        slots
+    }
+
+    fn contains(&self, widget: WidgetKind) -> bool {
+        self.slots.iter().any(|slot| slot.widget == widget)
+    }
+}
+
+/// Current condition shown as a chip in the weather widget
+/// (`widgets.metrics`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Metric {
+    Wind,
+    Gusts,
+    Pressure,
+    Uv,
+    Visibility,
+    DewPoint,
+    CloudCover,
+}
+
+impl Metric {
+    pub fn key(self) -> &'static str {
+        match self {
+            Metric::Wind => "wind",
+            Metric::Gusts => "gusts",
+            Metric::Pressure => "pressure",
+            Metric::Uv => "uv",
+            Metric::Visibility => "visibility",
+            Metric::DewPoint => "dew-point",
+            Metric::CloudCover => "cloud-cover",
+        }
+    }
+}
+
+/// How weather conditions and labels are drawn: the bundled monochrome SVG
+/// set, or Noto Emoji glyphs.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IconTheme {
+    #[default]
+    Svg,
+    Emoji,
+}
+
+impl IconTheme {
+    pub fn key(self) -> &'static str {
+        match self {
+            IconTheme::Svg => "svg",
+            IconTheme::Emoji => "emoji",
+        }
+    }
+}
+
+/// Which hourly forecast entries the weather widget shows: up to `count`
+/// entries on clock hours divisible by `interval_hours` (e.g. 12, 15, 18, 21),
+/// no further than `horizon_hours` ahead.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HourlySampling {
+    pub interval_hours: u32,
+    pub count: usize,
+    pub horizon_hours: u32,
+}
+
+/// On-disk layout of `dashboard.toml`. Every section is optional so a partial
+/// file only overrides what it mentions.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ConfigFile {
+    units: Units,
+    locale: Locale,
+    timezone: Option<String>,
+    icons: IconTheme,
+    location: LocationSection,
+    display: DisplaySection,
+    devices: HashMap<String, DeviceEntry>,
+    widgets: WidgetsSection,
+    forecast: ForecastSection,
+    tokens: TokensSection,
+    alerts: AlertsSection,
+    dashboards: HashMap<String, LocationSet>,
+    geocoding: GeocodingSection,
+    cache: CacheSection,
+    timeouts: TimeoutsSection,
+    prerender: Vec<PrerenderEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct LocationSection {
+    latitude: f64,
+    longitude: f64,
+    provider: ProviderKind,
+}
+
+impl Default for LocationSection {
+    fn default() -> Self {
+        Self {
+            latitude: 40.7128,
+            longitude: -74.0060,
+            provider: ProviderKind::default(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct DisplaySection {
+    device: String,
+    width: Option<u32>,
+    height: Option<u32>,
+}
+
+impl Default for DisplaySection {
+    fn default() -> Self {
+        Self {
+            device: DEFAULT_DEVICE.to_string(),
+            width: None,
+            height: None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct WidgetsSection {
+    columns: u8,
+    enabled: Vec<SlotEntry>,
+    metrics: Vec<Metric>,
+}
+
+impl Default for WidgetsSection {
+    fn default() -> Self {
+        Self {
+            columns: 1,
+            enabled: vec![
+                SlotEntry::Name(WidgetKind::Weather),
+                SlotEntry::Name(WidgetKind::Wanikani),
+            ],
+            metrics: vec![Metric::Wind, Metric::Pressure, Metric::Uv, Metric::DewPoint],
+        }
+    }
+}
+
+/// A `widgets.enabled` entry: a bare name spans the full width, a table such
+/// as `{ widget = "astronomy", span = 2 }` sets the span.
+#[derive(Deserialize)]
+#[serde(try_from = "toml::Value")]
+enum SlotEntry {
+    Name(WidgetKind),
+    Table(SlotTable),
+}
+
+/// Picks the variant by shape first, so a bad table reports the offending
+/// field instead of serde's generic untagged enum error.
+impl TryFrom<toml::Value> for SlotEntry {
+    type Error = toml::de::Error;
+
+    fn try_from(value: toml::Value) -> Result<Self, Self::Error> {
+        match value {
+            toml::Value::Table(_) => value.try_into().map(SlotEntry::Table),
+            _ => value.try_into().map(SlotEntry::Name),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SlotTable {
+    widget: WidgetKind,
+    span: Option<u8>,
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ForecastSection {
+    daily_days: u8,
+    hourly_interval_hours: u32,
+    hourly_count: usize,
+    hourly_horizon_hours: u32,
+    chart_hours: u32,
+    compare_history: bool,
+}
+
+impl Default for ForecastSection {
+    fn default() -> Self {
+        Self {
+            daily_days: DEFAULT_DAILY_DAYS,
+            hourly_interval_hours: DEFAULT_HOURLY_INTERVAL_HOURS,
+            hourly_count: DEFAULT_HOURLY_COUNT,
+            hourly_horizon_hours: DEFAULT_HOURLY_HORIZON_HOURS,
+            chart_hours: DEFAULT_CHART_HOURS,
+            compare_history: true,
+        }
+    }
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct TokensSection {
+    wanikani: Option<String>,
+    openweathermap: Option<String>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct AlertsSection {
+    source: Option<AlertSourceKind>,
+    country: Option<String>,
+    region: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct GeocodingSection {
+    provider: GeocoderKind,
+    cache_path: PathBuf,
+}
+
+impl Default for GeocodingSection {
+    fn default() -> Self {
+        Self {
+            provider: GeocoderKind::default(),
+            cache_path: PathBuf::from(DEFAULT_GEOCODING_CACHE_PATH),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct CacheSection {
+    weather_ttl_secs: u64,
+}
+
+impl Default for CacheSection {
+    fn default() -> Self {
+        Self {
+            weather_ttl_secs: DEFAULT_WEATHER_CACHE_TTL_SECS,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct TimeoutsSection {
+    source_secs: u64,
+    render_secs: u64,
+}
+
+impl Default for TimeoutsSection {
+    fn default() -> Self {
+        Self {
+            source_secs: DEFAULT_SOURCE_TIMEOUT_SECS,
+            render_secs: DEFAULT_RENDER_DEADLINE_SECS,
+        }
+    }
+}
+
+/// A `[[prerender]]` table: a `[dashboards]` id, or coordinates (the
+/// `[location]` ones if left out), and a device profile.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PrerenderEntry {
+    dashboard: Option<String>,
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+    device: Option<String>,
+    #[serde(default = "default_prerender_interval")]
+    interval_secs: u64,
+}
+
+fn default_prerender_interval() -> u64 {
+    DEFAULT_PRERENDER_INTERVAL_SECS
+}
+
+impl ConfigFile {
+    /// Applies the legacy environment variables, read through `var`, on top
+    /// of the file's values.
+    fn apply_overrides(&mut self, var: impl Fn(&str) -> Option<String>) -> anyhow::Result<()> {
+        if let Some(latitude) = env_override(&var, "DEFAULT_LATITUDE")? {
+            self.location.latitude = latitude;
+        }
+        if let Some(longitude) = env_override(&var, "DEFAULT_LONGITUDE")? {
+            self.location.longitude = longitude;
+        }
+        if let Some(width) = env_override(&var, "DASHBOARD_WIDTH")? {
+            self.display.width = Some(width);
+        }
+        if let Some(height) = env_override(&var, "DASHBOARD_HEIGHT")? {
+            self.display.height = Some(height);
+        }
+        if let Some(ttl) = env_override(&var, "WEATHER_CACHE_TTL_SECS")? {
+            self.cache.weather_ttl_secs = ttl;
+        }
+        if let Some(token) = var("WANIKANI_API_TOKEN") {
+            self.tokens.wanikani = Some(token);
+        }
+        if let Some(key) = var("OPENWEATHERMAP_API_KEY") {
+            self.tokens.openweathermap = Some(key);
+        }
+        Ok(())
+    }
+}
+
+impl DashboardConfig {
+    /// Loads `path` if it exists (falling back to defaults otherwise), applies
+    /// the legacy environment variable overrides and validates the result.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let mut file = if path.exists() {
+            let text = fs::read_to_string(path)
+                .with_context(|| format!("failed to read {}", path.display()))?;
+            toml::from_str::<ConfigFile>(&text)
+                .with_context(|| format!("failed to parse {}", path.display()))?
+        } else if path == Path::new(DEFAULT_CONFIG_PATH) {
+            ConfigFile::default()
+        } else {
+            bail!("config file {} does not exist", path.display());
+        };
+
+        file.apply_overrides(|name| env::var(name).ok())?;
+        Self::from_file(file).with_context(|| format!("invalid config {}", path.display()))
+    }
+
+    fn from_file(file: ConfigFile) -> anyhow::Result<Self> {
+        let ConfigFile {
+            units,
+            locale,
+            timezone,
+            icons,
+            location,
+            display,
+            devices,
+            widgets,
+            forecast,
+            tokens,
+            alerts,
+            dashboards,
+            geocoding,
+            cache,
+            timeouts,
+            prerender,
+        } = file;
+
+        validate_coordinates("location", location.latitude, location.longitude)?;
+        for (id, set) in &dashboards {
+            if set.secondary.len() > MAX_SECONDARY_LOCATIONS {
+                bail!(
+                    "dashboards.{id}.secondary lists {} locations, at most {MAX_SECONDARY_LOCATIONS} fit",
+                    set.secondary.len()
+                );
+            }
+            for (field, place) in std::iter::once(("primary", &set.primary))
+                .chain(set.secondary.iter().map(|place| ("secondary", place)))
+            {
+                let field = format!("dashboards.{id}.{field}");
+                if place.name.trim().is_empty() {
+                    bail!("{field}.name must not be empty");
+                }
+                validate_coordinates(&field, place.latitude, place.longitude)?;
+            }
+        }
+
+        let devices = devices
+            .iter()
+            .map(|(name, entry)| {
+                DeviceProfile::from_entry(name, entry)
+                    .map(|profile| (name.clone(), profile))
+                    .with_context(|| format!("devices.{name}"))
+            })
+            .collect::<anyhow::Result<HashMap<_, _>>>()?;
+
+        let mut default_device = lookup_device(&devices, &display.device)
+            .with_context(|| format!("display.device: unknown device `{}`", display.device))?;
+        if let Some(width) = display.width {
+            validate_dimension("display.width", width)?;
+            default_device.width = width;
+        }
+        if let Some(height) = display.height {
+            validate_dimension("display.height", height)?;
+            default_device.height = height;
+        }
+        if cache.weather_ttl_secs == 0 {
+            bail!("cache.weather_ttl_secs must be greater than 0");
+        }
+        if !(3..=7).contains(&forecast.daily_days) {
+            bail!(
+                "forecast.daily_days must be between 3 and 7, got {}",
+                forecast.daily_days
+            );
+        }
+        if forecast.hourly_interval_hours == 0 || 24 % forecast.hourly_interval_hours != 0 {
+            bail!(
+                "forecast.hourly_interval_hours must divide 24 (1, 2, 3, 4, 6, 8 or 12), got {}",
+                forecast.hourly_interval_hours
+            );
+        }
+        if !(1..=8).contains(&forecast.hourly_count) {
+            bail!(
+                "forecast.hourly_count must be between 1 and 8, got {}",
+                forecast.hourly_count
+            );
+        }
+        if !(forecast.hourly_interval_hours..=168).contains(&forecast.hourly_horizon_hours) {
+            bail!(
+                "forecast.hourly_horizon_hours must be between hourly_interval_hours and 168, got {}",
+                forecast.hourly_horizon_hours
+            );
+        }
+        if !(24..=48).contains(&forecast.chart_hours) {
+            bail!(
+                "forecast.chart_hours must be between 24 and 48, got {}",
+                forecast.chart_hours
+            );
+        }
+        if !(1..=MAX_LAYOUT_COLUMNS).contains(&widgets.columns) {
+            bail!(
+                "widgets.columns must be between 1 and {MAX_LAYOUT_COLUMNS}, got {}",
+                widgets.columns
+            );
+        }
+        let slots: Vec<WidgetSlot> = widgets
+            .enabled
+            .into_iter()
+            .map(|entry| match entry {
+                SlotEntry::Name(widget) => WidgetSlot {
+                    widget,
+                    span: widgets.columns,
+                },
+                SlotEntry::Table(table) => WidgetSlot {
+                    widget: table.widget,
+                    span: table.span.unwrap_or(widgets.columns),
+                },
+            })
+            .collect();
+        for (index, slot) in slots.iter().enumerate() {
+            if slots[..index]
+                .iter()
+                .any(|other| other.widget == slot.widget)
+            {
+                bail!(
+                    "widgets.enabled lists `{}` more than once",
+                    slot.widget.key()
+                );
+            }
+            if !(1..=widgets.columns).contains(&slot.span) {
+                bail!(
+                    "widgets.enabled: `{}` spans {} columns, but there are {}",
+                    slot.widget.key(),
+                    slot.span,
+                    widgets.columns
+                );
+            }
+            let min_span = slot.widget.min_span(widgets.columns);
+            if slot.span < min_span {
+                bail!(
+                    "widgets.enabled: `{}` needs at least {min_span} of the {} columns, got {}",
+                    slot.widget.key(),
+                    widgets.columns,
+                    slot.span
+                );
+            }
+        }
+        if timeouts.source_secs == 0 || timeouts.render_secs == 0 {
+            bail!("timeouts.source_secs and timeouts.render_secs must be at least 1");
+        }
+        for (index, metric) in widgets.metrics.iter().enumerate() {
+            if widgets.metrics[..index].contains(metric) {
+                bail!("widgets.metrics lists `{}` more than once", metric.key());
+            }
+        }
+
+        let timezone = timezone
+            .map(|name| {
+                Tz::from_str(&name).map_err(|_| anyhow::anyhow!("unknown timezone `{name}`"))
+            })
+            .transpose()?;
+        let wanikani_token = tokens.wanikani.filter(|token| !token.trim().is_empty());
+        let openweathermap_key = tokens.openweathermap.filter(|key| !key.trim().is_empty());
+        if location.provider == ProviderKind::OpenWeatherMap && openweathermap_key.is_none() {
+            bail!("location.provider is `openweathermap` but tokens.openweathermap is not set");
+        }
+
+        let country = alerts.country.filter(|country| !country.trim().is_empty());
+        let region = alerts.region.filter(|region| !region.trim().is_empty());
+        if alerts.source == Some(AlertSourceKind::Meteoalarm)
+            && (country.is_none() || region.is_none())
+        {
+            bail!("alerts.source is `meteoalarm` but alerts.country or alerts.region is not set");
+        }
+        let alerts = alerts.source.map(|source| AlertSettings {
+            source,
+            country,
+            region,
+        });
+
+        let mut prerender_settings: Vec<PrerenderSettings> = Vec::new();
+        for (index, entry) in prerender.into_iter().enumerate() {
+            let field = format!("prerender[{index}]");
+            let target = match (entry.dashboard, entry.latitude, entry.longitude) {
+                (Some(id), None, None) => {
+                    if !dashboards.contains_key(&id) {
+                        bail!("{field}.dashboard: unknown dashboard `{id}`");
+                    }
+                    RenderTarget::LocationSet(id)
+                }
+                (Some(_), _, _) => {
+                    bail!("{field}: set either dashboard or latitude and longitude, not both")
+                }
+                (None, Some(latitude), Some(longitude)) => {
+                    validate_coordinates(&field, latitude, longitude)?;
+                    RenderTarget::Location(Coordinates {
+                        latitude,
+                        longitude,
+                    })
+                }
+                (None, None, None) => RenderTarget::Location(Coordinates {
+                    latitude: location.latitude,
+                    longitude: location.longitude,
+                }),
+                (None, _, _) => bail!("{field}: latitude and longitude go together"),
+            };
+            let device = entry.device.unwrap_or_else(|| default_device.name.clone());
+            if lookup_device(&devices, &device).is_none() {
+                bail!("{field}.device: unknown device `{device}`");
+            }
+            if entry.interval_secs < MIN_PRERENDER_INTERVAL_SECS {
+                bail!(
+                    "{field}.interval_secs must be at least {MIN_PRERENDER_INTERVAL_SECS}, got {}",
+                    entry.interval_secs
+                );
+            }
+            let settings = PrerenderSettings {
+                target,
+                device,
+                interval: time::Duration::from_secs(entry.interval_secs),
+            };
+            if prerender_settings
+                .iter()
+                .any(|other| other.key() == settings.key())
+            {
+                bail!("{field} repeats an earlier entry's target and device");
+            }
+            prerender_settings.push(settings);
+        }
+
+        Ok(Self {
+            latitude: location.latitude,
+            longitude: location.longitude,
+            provider: location.provider,
+            display: default_device,
+            devices,
+            units,
+            locale,
+            timezone,
+            icons,
+            layout: Layout {
+                columns: widgets.columns,
+                slots,
+            },
+            metrics: widgets.metrics,
+            daily_days: forecast.daily_days,
+            hourly: HourlySampling {
+                interval_hours: forecast.hourly_interval_hours,
+                count: forecast.hourly_count,
+                horizon_hours: forecast.hourly_horizon_hours,
+            },
+            chart_hours: forecast.chart_hours,
+            compare_history: forecast.compare_history,
+            wanikani_token,
+            openweathermap_key,
+            alerts,
+            dashboards,
+            geocoding: GeocodingSettings {
+                provider: geocoding.provider,
+                cache_path: geocoding.cache_path,
+            },
+            cache_ttl: Duration::seconds(cache.weather_ttl_secs as i64),
+            source_timeout: time::Duration::from_secs(timeouts.source_secs),
+            render_deadline: time::Duration::from_secs(timeouts.render_secs),
+            prerender: prerender_settings,
+        })
+    }
+
+    pub fn is_enabled(&self, widget: WidgetKind) -> bool {
+        self.layout.contains(widget)
+    }
+
+    /// Days of forecast to request: enough for the daily widget and for the
+    /// hourly horizon (or chart span) counted from late in the current day,
+    /// so the hourly entries run past midnight.
+    pub fn forecast_days(&self) -> u8 {
+        let mut horizon_hours = self.hourly.horizon_hours;
+        if self.is_enabled(WidgetKind::Chart) {
+            horizon_hours = horizon_hours.max(self.chart_hours);
+        }
+        let hourly_days = horizon_hours.div_ceil(24) as u8 + 1;
+        self.daily_days.max(hourly_days).min(MAX_FORECAST_DAYS)
+    }
+
+    pub fn coordinates(&self, path: &CoordinatesPath) -> Coordinates {
+        Coordinates {
+            latitude: path.latitude,
+            longitude: path.longitude,
+        }
+    }
+
+    /// Resolves the device profile for a request: `?device=` picks a
+    /// configured or built-in profile, `?width=`/`?height=` override its
+    /// resolution, `?levels=`/`?dither=` its quantization and `?rotate=` its
+    /// mounting orientation.
+    pub fn device(&self, params: &RenderParams) -> anyhow::Result<DeviceProfile> {
+        let mut profile = match params.device.as_deref() {
+            Some(name) => lookup_device(&self.devices, name)
+                .with_context(|| format!("unknown device `{name}`"))?,
+            None => self.display.clone(),
+        };
+
+        if let Some(width) = params.width {
+            validate_dimension("width", width)?;
+            profile.width = width;
+        }
+        if let Some(height) = params.height {
+            validate_dimension("height", height)?;
+            profile.height = height;
+        }
+        if let Some(levels) = params.levels {
+            profile.gray_levels = levels;
+        }
+        if let Some(dither) = params.dither {
+            profile.dither = dither;
+        }
+        if let Some(rotation) = params.rotate {
+            profile.rotation = rotation;
+        }
+        profile.validate()?;
+
+        Ok(profile)
+    }
+}
+
+fn validate_coordinates(field: &str, latitude: f64, longitude: f64) -> anyhow::Result<()> {
+    if !(-90.0..=90.0).contains(&latitude) {
+        bail!("{field}.latitude must be between -90 and 90, got {latitude}");
+    }
+    if !(-180.0..=180.0).contains(&longitude) {
+        bail!("{field}.longitude must be between -180 and 180, got {longitude}");
+    }
+    Ok(())
+}
+
+fn lookup_device(devices: &HashMap<String, DeviceProfile>, name: &str) -> Option<DeviceProfile> {
+    devices
+        .get(name)
+        .cloned()
+        .or_else(|| DeviceProfile::builtin(name))
+}
+
+/// Reads an optional environment override, rejecting values that don't parse
+/// instead of silently falling back to the default.
+fn env_override<T>(var: impl Fn(&str) -> Option<String>, name: &str) -> anyhow::Result<Option<T>>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    match var(name) {
+        Some(value) => value
+            .trim()
+            .parse()
+            .map(Some)
+            .map_err(|err| anyhow::anyhow!("{name}: invalid value `{value}`: {err}")),
+        None => Ok(None),
+    }
+}
+
+pub fn config_path(cli_path: Option<PathBuf>) -> PathBuf {
+    cli_path
+        .or_else(|| env::var_os("DASHBOARD_CONFIG").map(PathBuf::from))
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_CONFIG_PATH))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> anyhow::Result<DashboardConfig> {
+        DashboardConfig::from_file(toml::from_str(text)?)
+    }
+
+    /// The full error chain, as it is logged.
+    fn error(text: &str) -> String {
+        format!("{:#}", parse(text).expect_err("config should be rejected"))
+    }
+
+    #[test]
+    fn defaults_fill_an_empty_file() {
+        let config = parse("").unwrap();
+        assert_eq!(config.display.name, DEFAULT_DEVICE);
+        assert_eq!(config.layout.slots(false).len(), 2);
+    }
+
+    #[test]
+    fn rejects_out_of_range_latitude() {
+        assert_eq!(
+            error("[location]\nlatitude = 91.5\nlongitude = 13.4"),
+            "location.latitude must be between -90 and 90, got 91.5"
+        );
+        assert_eq!(
+            error(
+                "[dashboards.family]\nprimary = { name = \"Home\", latitude = -95.0, longitude = 0.0 }"
+            ),
+            "dashboards.family.primary.latitude must be between -90 and 90, got -95"
+        );
+    }
+
+    #[test]
+    fn rejects_zero_dimensions() {
+        assert_eq!(
+            error("[display]\nwidth = 0"),
+            "display.width must be between 1 and 4096, got 0"
+        );
+        assert_eq!(
+            error("[display]\nheight = 0"),
+            "display.height must be between 1 and 4096, got 0"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_keys() {
+        assert!(error("[display]\ndevcie = \"kindle4\"").contains("unknown field `devcie`"));
+        assert!(error("colour = \"red\"").contains("unknown field `colour`"));
+        let slot = error("[widgets]\nenabled = [{ widget = \"daily\", width = 1 }]");
+        assert!(slot.contains("unknown field `width`"), "{slot}");
+        let name = error("[widgets]\nenabled = [\"weather\", \"radar\"]");
+        assert!(name.contains("unknown variant `radar`"), "{name}");
+    }
+
+    #[test]
+    fn rejects_bad_spans() {
+        assert_eq!(
+            error("[widgets]\ncolumns = 2\nenabled = [{ widget = \"daily\", span = 3 }]"),
+            "widgets.enabled: `daily` spans 3 columns, but there are 2"
+        );
+        assert_eq!(
+            error("[widgets]\ncolumns = 2\nenabled = [{ widget = \"daily\", span = 0 }]"),
+            "widgets.enabled: `daily` spans 0 columns, but there are 2"
+        );
+        assert_eq!(
+            error("[widgets]\ncolumns = 2\nenabled = [{ widget = \"weather\", span = 1 }]"),
+            "widgets.enabled: `weather` needs at least 2 of the 2 columns, got 1"
+        );
+        assert_eq!(
+            error("[widgets]\ncolumns = 3\nenabled = [{ widget = \"astronomy\", span = 1 }]"),
+            "widgets.enabled: `astronomy` needs at least 2 of the 3 columns, got 1"
+        );
+        let layout = parse(
+            "[widgets]\ncolumns = 2\nenabled = [\"weather\", { widget = \"chart\", span = 1 }]",
+        )
+        .unwrap()
+        .layout;
+        assert_eq!(layout.slots[1].span, 1);
+    }
+
+    #[test]
+    fn rejects_unknown_devices() {
+        assert_eq!(
+            error("[display]\ndevice = \"kobo\""),
+            "display.device: unknown device `kobo`"
+        );
+        let config = parse("").unwrap();
+        let params = RenderParams {
+            device: Some("kobo".to_string()),
+            ..RenderParams::default()
+        };
+        assert_eq!(
+            config.device(&params).unwrap_err().to_string(),
+            "unknown device `kobo`"
+        );
+    }
+
+    #[test]
+    fn environment_overrides_win() {
+        let text = "[location]\nlatitude = 52.52\nlongitude = 13.41\n\n[display]\nwidth = 600\n\n[tokens]\nwanikani = \"from-file\"";
+        let mut file: ConfigFile = toml::from_str(text).unwrap();
+        file.apply_overrides(|name| match name {
+            "DEFAULT_LATITUDE" => Some("59.91".to_string()),
+            "DASHBOARD_WIDTH" => Some(" 758 ".to_string()),
+            "WANIKANI_API_TOKEN" => Some("from-env".to_string()),
+            _ => None,
+        })
+        .unwrap();
+        let config = DashboardConfig::from_file(file).unwrap();
+
+        assert_eq!(config.latitude, 59.91);
+        assert_eq!(config.longitude, 13.41);
+        assert_eq!(config.display.width, 758);
+        assert_eq!(config.wanikani_token.as_deref(), Some("from-env"));
+
+        let mut file = ConfigFile::default();
+        let err = file
+            .apply_overrides(|name| (name == "DASHBOARD_HEIGHT").then(|| "tall".to_string()))
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("DASHBOARD_HEIGHT: invalid value `tall`")
+        );
+    }
+}
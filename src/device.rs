@@ -0,0 +1,198 @@
+use anyhow::bail;
+use image::{GrayImage, ImageFormat, imageops};
+use serde::Deserialize;
+
+use crate::dither::Dither;
+
+/// Pixel density the templates are laid out for; a 300 PPI panel renders
+/// at 2 px per pt.
+const REFERENCE_PPI: f32 = 150.0;
+const MAX_DIMENSION: u32 = 4096;
+pub const DEFAULT_DEVICE: &str = "paperwhite3";
+
+#[derive(Clone, Debug)]
+pub struct DeviceProfile {
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+    pub ppi: u32,
+    pub gray_levels: u16,
+    pub dither: Dither,
+    pub rotation: Rotation,
+    pub format: OutputFormat,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "u16")]
+pub enum Rotation {
+    #[default]
+    None,
+    Clockwise90,
+    Rotate180,
+    Clockwise270,
+}
+
+/// Clockwise rotation applied to the rendered bitmap so it matches the
+/// panel's native framebuffer orientation.
+impl Rotation {
+    pub fn degrees(self) -> u16 {
+        match self {
+            Rotation::None => 0,
+            Rotation::Clockwise90 => 90,
+            Rotation::Rotate180 => 180,
+            Rotation::Clockwise270 => 270,
+        }
+    }
+
+    pub fn is_sideways(self) -> bool {
+        matches!(self, Rotation::Clockwise90 | Rotation::Clockwise270)
+    }
+
+    pub fn apply(self, image: GrayImage) -> GrayImage {
+        match self {
+            Rotation::None => image,
+            Rotation::Clockwise90 => imageops::rotate90(&image),
+            Rotation::Rotate180 => imageops::rotate180(&image),
+            Rotation::Clockwise270 => imageops::rotate270(&image),
+        }
+    }
+}
+
+impl TryFrom<u16> for Rotation {
+    type Error = String;
+
+    fn try_from(degrees: u16) -> Result<Self, Self::Error> {
+        match degrees {
+            0 => Ok(Rotation::None),
+            90 => Ok(Rotation::Clockwise90),
+            180 => Ok(Rotation::Rotate180),
+            270 => Ok(Rotation::Clockwise270),
+            other => Err(format!("rotation must be 0, 90, 180 or 270, got {other}")),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[default]
+    Png,
+    Bmp,
+}
+
+impl OutputFormat {
+    pub fn image_format(self) -> ImageFormat {
+        match self {
+            OutputFormat::Png => ImageFormat::Png,
+            OutputFormat::Bmp => ImageFormat::Bmp,
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            OutputFormat::Png => "image/png",
+            OutputFormat::Bmp => "image/bmp",
+        }
+    }
+}
+
+/// A `[devices.<name>]` entry. Fields left out are taken from `base`, which
+/// defaults to the built-in profile of the same name (if any).
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DeviceEntry {
+    pub base: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub ppi: Option<u32>,
+    pub gray_levels: Option<u16>,
+    pub dither: Option<Dither>,
+    pub rotation: Option<Rotation>,
+    pub format: Option<OutputFormat>,
+}
+
+impl DeviceProfile {
+    /// Built-in profiles for the devices we run; resolution and PPI are the
+    /// panel's native portrait values.
+    pub fn builtin(name: &str) -> Option<Self> {
+        let (width, height, ppi) = match name {
+            "paperwhite3" => (1072, 1448, 300),
+            "kindle4" => (600, 800, 167),
+            "oasis" => (1264, 1680, 300),
+            "kobo-clara" => (1072, 1448, 300),
+            "kobo-libra2" => (1264, 1680, 300),
+            _ => return None,
+        };
+
+        Some(Self {
+            name: name.to_string(),
+            width,
+            height,
+            ppi,
+            gray_levels: 16,
+            dither: Dither::FloydSteinberg,
+            rotation: Rotation::None,
+            format: OutputFormat::Png,
+        })
+    }
+
+    pub fn from_entry(name: &str, entry: &DeviceEntry) -> anyhow::Result<Self> {
+        let base_name = entry.base.as_deref().unwrap_or(name);
+        let base = match Self::builtin(base_name) {
+            Some(base) => base,
+            None if entry.base.is_some() => bail!("unknown base device `{base_name}`"),
+            None => Self::builtin(DEFAULT_DEVICE).expect("default device is built in"),
+        };
+
+        let profile = Self {
+            name: name.to_string(),
+            width: entry.width.unwrap_or(base.width),
+            height: entry.height.unwrap_or(base.height),
+            ppi: entry.ppi.unwrap_or(base.ppi),
+            gray_levels: entry.gray_levels.unwrap_or(base.gray_levels),
+            dither: entry.dither.unwrap_or(base.dither),
+            rotation: entry.rotation.unwrap_or(base.rotation),
+            format: entry.format.unwrap_or(base.format),
+        };
+        profile.validate()?;
+
+        Ok(profile)
+    }
+
+    pub fn validate(&self) -> anyhow::Result<()> {
+        validate_dimension("width", self.width)?;
+        validate_dimension("height", self.height)?;
+        if self.ppi == 0 {
+            bail!("ppi must be greater than 0");
+        }
+        if ![2, 4, 16, 256].contains(&self.gray_levels) {
+            bail!(
+                "gray_levels must be 2, 4, 16 or 256, got {}",
+                self.gray_levels
+            );
+        }
+        Ok(())
+    }
+
+    /// Size of the page as the viewer sees it. Sideways-mounted panels swap
+    /// width and height; the bitmap is rotated back before encoding.
+    pub fn logical_size(&self) -> (u32, u32) {
+        if self.rotation.is_sideways() {
+            (self.height, self.width)
+        } else {
+            (self.width, self.height)
+        }
+    }
+
+    /// Device pixels per Typst point.
+    pub fn pixel_per_pt(&self) -> f32 {
+        self.ppi as f32 / REFERENCE_PPI
+    }
+}
+
+pub fn validate_dimension(name: &str, value: u32) -> anyhow::Result<()> {
+    if value == 0 || value > MAX_DIMENSION {
+        bail!("{name} must be between 1 and {MAX_DIMENSION}, got {value}");
+    }
+    Ok(())
+}
@@ -0,0 +1,263 @@
+use anyhow::{Context, bail};
+use image::GrayImage;
+use serde::Deserialize;
+
+/// Error diffusion / ordered dithering applied when reducing the grayscale
+/// render to the panel's number of gray levels.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Dither {
+    /// Snap every pixel to the nearest level.
+    None,
+    #[default]
+    FloydSteinberg,
+    Atkinson,
+    Bayer,
+}
+
+const BAYER_8X8: [[u8; 8]; 8] = [
+    [0, 32, 8, 40, 2, 34, 10, 42],
+    [48, 16, 56, 24, 50, 18, 58, 26],
+    [12, 44, 4, 36, 14, 46, 6, 38],
+    [60, 28, 52, 20, 62, 30, 54, 22],
+    [3, 35, 11, 43, 1, 33, 9, 41],
+    [51, 19, 59, 27, 49, 17, 57, 25],
+    [15, 47, 7, 39, 13, 45, 5, 37],
+    [63, 31, 55, 23, 61, 29, 53, 21],
+];
+
+/// Reduces `image` in place to `levels` evenly spaced gray values. Pixels keep
+/// their 8-bit encoding (e.g. 0, 85, 170, 255 for 4 levels) so the result can
+/// still be handed to any encoder; [`encode_png`] packs them down.
+pub fn quantize(image: &mut GrayImage, levels: u16, dither: Dither) {
+    if levels >= 256 {
+        return;
+    }
+
+    match dither {
+        Dither::None => {
+            for pixel in image.pixels_mut() {
+                pixel.0[0] = nearest_level(pixel.0[0] as f32, levels);
+            }
+        }
+        Dither::Bayer => {
+            let step = 255.0 / (levels - 1) as f32;
+            for (x, y, pixel) in image.enumerate_pixels_mut() {
+                let threshold = (BAYER_8X8[y as usize % 8][x as usize % 8] as f32 + 0.5) / 64.0;
+                let value = pixel.0[0] as f32 + (threshold - 0.5) * step;
+                pixel.0[0] = nearest_level(value, levels);
+            }
+        }
+        Dither::FloydSteinberg => diffuse(
+            image,
+            levels,
+            &[
+                (1, 0, 7.0 / 16.0),
+                (-1, 1, 3.0 / 16.0),
+                (0, 1, 5.0 / 16.0),
+                (1, 1, 1.0 / 16.0),
+            ],
+        ),
+        // Atkinson only spreads 6/8 of the error, which keeps flat areas clean
+        // at the cost of some contrast in the highlights and shadows.
+        Dither::Atkinson => diffuse(
+            image,
+            levels,
+            &[
+                (1, 0, 1.0 / 8.0),
+                (2, 0, 1.0 / 8.0),
+                (-1, 1, 1.0 / 8.0),
+                (0, 1, 1.0 / 8.0),
+                (1, 1, 1.0 / 8.0),
+                (0, 2, 1.0 / 8.0),
+            ],
+        ),
+    }
+}
+
+fn diffuse(image: &mut GrayImage, levels: u16, kernel: &[(i64, i64, f32)]) {
+    let (width, height) = (image.width() as i64, image.height() as i64);
+    let mut values: Vec<f32> = image.as_raw().iter().map(|&v| v as f32).collect();
+
+    for y in 0..height {
+        for x in 0..width {
+            let index = (y * width + x) as usize;
+            let old = values[index];
+            let new = nearest_level(old, levels);
+            let error = old - new as f32;
+            image.put_pixel(x as u32, y as u32, image::Luma([new]));
+
+            for &(dx, dy, weight) in kernel {
+                let (nx, ny) = (x + dx, y + dy);
+                if nx >= 0 && nx < width && ny < height {
+                    values[(ny * width + nx) as usize] += error * weight;
+                }
+            }
+        }
+    }
+}
+
+fn nearest_level(value: f32, levels: u16) -> u8 {
+    let max = (levels - 1) as f32;
+    let index = (value.clamp(0.0, 255.0) / 255.0 * max).round();
+    (index * 255.0 / max).round() as u8
+}
+
+/// Encodes a quantized image as a grayscale PNG with the smallest bit depth
+/// that holds `levels` (1, 2, 4 or 8 bits per pixel).
+pub fn encode_png(image: &GrayImage, levels: u16) -> anyhow::Result<Vec<u8>> {
+    let bit_depth = match levels {
+        2 => png::BitDepth::One,
+        4 => png::BitDepth::Two,
+        16 => png::BitDepth::Four,
+        256 => png::BitDepth::Eight,
+        other => bail!("unsupported gray level count {other}"),
+    };
+    let bits = bit_depth as u32;
+    let max = (levels - 1) as u32;
+
+    let row_bytes = (image.width() * bits).div_ceil(8) as usize;
+    let mut data = vec![0u8; row_bytes * image.height() as usize];
+    for (y, row) in image.rows().enumerate() {
+        let packed = &mut data[y * row_bytes..(y + 1) * row_bytes];
+        for (x, pixel) in row.enumerate() {
+            let sample = (pixel.0[0] as u32 * max + 127) / 255;
+            let bit_offset = x as u32 * bits;
+            let shift = 8 - bits - bit_offset % 8;
+            packed[(bit_offset / 8) as usize] |= (sample << shift) as u8;
+        }
+    }
+
+    let mut bytes = Vec::new();
+    let mut encoder = png::Encoder::new(&mut bytes, image.width(), image.height());
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(bit_depth);
+    let mut writer = encoder
+        .write_header()
+        .context("failed to write PNG header")?;
+    writer
+        .write_image_data(&data)
+        .context("failed to write PNG data")?;
+    writer.finish().context("failed to finish PNG")?;
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::io::Cursor;
+
+    use super::*;
+
+    /// Goldens are rewritten from the current output when this is set.
+    const UPDATE_GOLDENS: &str = "UPDATE_GOLDENS";
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/dither");
+
+    fn gradient() -> GrayImage {
+        image::open(format!("{FIXTURES}/gradient.png"))
+            .unwrap()
+            .into_luma8()
+    }
+
+    fn bit_depth(png: &[u8]) -> png::BitDepth {
+        let reader = png::Decoder::new(Cursor::new(png)).read_info().unwrap();
+        reader.info().bit_depth
+    }
+
+    /// Quantizes the gradient and compares the encoded result, pixel by pixel,
+    /// with `tests/fixtures/dither/<name>.png`.
+    fn assert_golden(dither: Dither, levels: u16, name: &str) {
+        let mut image = gradient();
+        quantize(&mut image, levels, dither);
+        let png = encode_png(&image, levels).unwrap();
+
+        let path = format!("{FIXTURES}/{name}.png");
+        if std::env::var_os(UPDATE_GOLDENS).is_some() {
+            fs::write(&path, &png).unwrap();
+        }
+        let golden = image::load_from_memory(&fs::read(&path).unwrap())
+            .unwrap()
+            .into_luma8();
+        let decoded = image::load_from_memory(&png).unwrap().into_luma8();
+
+        assert_eq!(decoded, image, "{name}: PNG doesn't round-trip");
+        assert_eq!(image, golden, "{name}: differs from its golden image");
+    }
+
+    #[test]
+    fn none_matches_goldens() {
+        assert_golden(Dither::None, 2, "none-1bpp");
+        assert_golden(Dither::None, 4, "none-2bpp");
+        assert_golden(Dither::None, 16, "none-4bpp");
+    }
+
+    #[test]
+    fn floyd_steinberg_matches_goldens() {
+        assert_golden(Dither::FloydSteinberg, 2, "floyd-steinberg-1bpp");
+        assert_golden(Dither::FloydSteinberg, 4, "floyd-steinberg-2bpp");
+        assert_golden(Dither::FloydSteinberg, 16, "floyd-steinberg-4bpp");
+    }
+
+    #[test]
+    fn atkinson_matches_goldens() {
+        assert_golden(Dither::Atkinson, 2, "atkinson-1bpp");
+        assert_golden(Dither::Atkinson, 4, "atkinson-2bpp");
+        assert_golden(Dither::Atkinson, 16, "atkinson-4bpp");
+    }
+
+    #[test]
+    fn bayer_matches_goldens() {
+        assert_golden(Dither::Bayer, 2, "bayer-1bpp");
+        assert_golden(Dither::Bayer, 4, "bayer-2bpp");
+        assert_golden(Dither::Bayer, 16, "bayer-4bpp");
+    }
+
+    #[test]
+    fn quantize_only_leaves_panel_levels() {
+        for dither in [
+            Dither::None,
+            Dither::FloydSteinberg,
+            Dither::Atkinson,
+            Dither::Bayer,
+        ] {
+            let mut image = gradient();
+            quantize(&mut image, 4, dither);
+            assert!(
+                image.pixels().all(|p| [0, 85, 170, 255].contains(&p.0[0])),
+                "{dither:?} left values between levels"
+            );
+        }
+    }
+
+    #[test]
+    fn one_bit_png_round_trips() {
+        // 13 pixels wide, so each row ends in a partly filled byte
+        let mut image =
+            GrayImage::from_fn(13, 3, |x, y| image::Luma([((x * 20 + y * 7) % 256) as u8]));
+        quantize(&mut image, 2, Dither::FloydSteinberg);
+        assert!(image.pixels().all(|p| p.0[0] == 0 || p.0[0] == 255));
+
+        let png = encode_png(&image, 2).unwrap();
+        assert_eq!(bit_depth(&png), png::BitDepth::One);
+        let decoded = image::load_from_memory(&png).unwrap().into_luma8();
+        assert_eq!(decoded, image);
+    }
+
+    #[test]
+    fn encode_png_uses_the_smallest_bit_depth() {
+        let image = gradient();
+        for (levels, depth) in [
+            (2, png::BitDepth::One),
+            (4, png::BitDepth::Two),
+            (16, png::BitDepth::Four),
+            (256, png::BitDepth::Eight),
+        ] {
+            let mut quantized = image.clone();
+            quantize(&mut quantized, levels, Dither::None);
+            let png = encode_png(&quantized, levels).unwrap();
+            assert_eq!(bit_depth(&png), depth, "{levels} levels");
+        }
+        assert!(encode_png(&image, 3).is_err());
+    }
+}
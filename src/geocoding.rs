@@ -0,0 +1,350 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use anyhow::Context;
+use futures::future::BoxFuture;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::Coordinates;
+use crate::locale::Locale;
+use crate::provider::{USER_AGENT, base_url};
+
+mod nominatim;
+mod open_meteo;
+
+use nominatim::Nominatim;
+use open_meteo::OpenMeteoGeocoding;
+
+/// Service place names are looked up with (`geocoding.provider`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GeocoderKind {
+    #[default]
+    OpenMeteo,
+    /// OpenStreetMap's search; slower, but finds streets and landmarks too.
+    Nominatim,
+}
+
+impl GeocoderKind {
+    fn key(self) -> &'static str {
+        match self {
+            GeocoderKind::OpenMeteo => "open-meteo",
+            GeocoderKind::Nominatim => "nominatim",
+        }
+    }
+}
+
+/// The `[geocoding]` section.
+#[derive(Clone, Debug)]
+pub struct GeocodingSettings {
+    pub provider: GeocoderKind,
+    /// File resolved names are kept in across restarts.
+    pub cache_path: PathBuf,
+}
+
+/// A search result, with names in the dashboard's language where the
+/// service has them.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Place {
+    pub name: String,
+    /// State, province or similar first-level division.
+    pub region: Option<String>,
+    pub country: Option<String>,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl Place {
+    pub fn coordinates(&self) -> Coordinates {
+        Coordinates {
+            latitude: self.latitude,
+            longitude: self.longitude,
+        }
+    }
+
+    /// "Springfield, Illinois, United States", leaving out missing parts and
+    /// regions named like the place itself.
+    pub fn label(&self) -> String {
+        let region = self.region.as_ref().filter(|region| **region != self.name);
+        std::iter::once(&self.name)
+            .chain(region)
+            .chain(self.country.as_ref())
+            .filter(|part| !part.is_empty())
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Whether a qualifier such as "Illinois" or "united states" names this
+    /// place's region or country.
+    fn matches(&self, qualifier: &str) -> bool {
+        let qualifier = qualifier.to_lowercase();
+        [&self.region, &self.country]
+            .into_iter()
+            .flatten()
+            .any(|part| part.to_lowercase().starts_with(&qualifier))
+    }
+}
+
+pub trait Geocoder: Send + Sync {
+    /// Places named `name` (or with that postal code), best match first.
+    fn search(&self, name: &str) -> BoxFuture<'_, anyhow::Result<Vec<Place>>>;
+}
+
+/// HTTP client and endpoints for the geocoding services, overridable through
+/// the environment like the weather providers', plus the on-disk cache of
+/// their answers.
+pub struct Geocoders {
+    http: Client,
+    open_meteo_base_url: String,
+    nominatim_base_url: String,
+    cache: Mutex<PlaceCache>,
+    /// Held while the cache file is written, so writes land in order.
+    saving: tokio::sync::Mutex<()>,
+}
+
+impl Geocoders {
+    pub fn new() -> Self {
+        let http = Client::builder()
+            .user_agent(USER_AGENT)
+            .build()
+            .expect("failed to build HTTP client");
+
+        Self {
+            http,
+            open_meteo_base_url: base_url(
+                "GEOCODING_API_BASE_URL",
+                "https://geocoding-api.open-meteo.com",
+            ),
+            nominatim_base_url: base_url(
+                "NOMINATIM_BASE_URL",
+                "https://nominatim.openstreetmap.org",
+            ),
+            cache: Mutex::new(PlaceCache::default()),
+            saving: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    fn get(&self, kind: GeocoderKind, locale: Locale) -> Box<dyn Geocoder> {
+        let http = self.http.clone();
+        match kind {
+            GeocoderKind::OpenMeteo => Box::new(OpenMeteoGeocoding {
+                http,
+                base_url: self.open_meteo_base_url.clone(),
+                language: locale.code(),
+            }),
+            GeocoderKind::Nominatim => Box::new(Nominatim {
+                http,
+                base_url: self.nominatim_base_url.clone(),
+                language: locale.code(),
+            }),
+        }
+    }
+
+    /// Candidates for `query`: a name or postal code, optionally narrowed
+    /// down by region and country after commas ("Springfield, Illinois").
+    /// Results of each search are cached on disk, so a place is only looked
+    /// up once. Searches that found nothing are asked again next time.
+    pub async fn resolve(
+        &self,
+        settings: &GeocodingSettings,
+        locale: Locale,
+        query: &str,
+    ) -> anyhow::Result<Vec<Place>> {
+        let mut parts = query.split(',').map(str::trim);
+        let name = parts.next().unwrap_or_default();
+        let qualifiers: Vec<&str> = parts.filter(|part| !part.is_empty()).collect();
+
+        let key = format!(
+            "{}:{}:{}",
+            settings.provider.key(),
+            locale.code(),
+            name.to_lowercase()
+        );
+        let cached = self.cache.lock().unwrap().get(&settings.cache_path, &key);
+        let places = match cached {
+            Some(places) => places,
+            None => {
+                let places = self.get(settings.provider, locale).search(name).await?;
+                if !places.is_empty() {
+                    self.cache
+                        .lock()
+                        .unwrap()
+                        .insert(&settings.cache_path, key, places.clone());
+                    self.save_cache(&settings.cache_path).await;
+                }
+                places
+            }
+        };
+
+        // A full label, as linked from the disambiguation page, picks its
+        // place even when its parts also fit others
+        if let Some(place) = places
+            .iter()
+            .find(|place| place.label().eq_ignore_ascii_case(query.trim()))
+        {
+            return Ok(vec![place.clone()]);
+        }
+
+        // Services list the same town more than once, e.g. as a boundary and
+        // as a point; keep the first of each label
+        let mut labels = Vec::new();
+        Ok(places
+            .into_iter()
+            .filter(|place| qualifiers.iter().all(|qualifier| place.matches(qualifier)))
+            .filter(|place| {
+                let label = place.label();
+                let new = !labels.contains(&label);
+                labels.push(label);
+                new
+            })
+            .collect())
+    }
+
+    /// Rewrites the cache file from a snapshot taken after any earlier write
+    /// finished. A failed write only costs a lookup after the next restart,
+    /// so it is logged and otherwise ignored.
+    async fn save_cache(&self, path: &Path) {
+        let _saving = self.saving.lock().await;
+        let text = self.cache.lock().unwrap().to_toml();
+        let result = match text {
+            Ok(text) => {
+                let path = path.to_path_buf();
+                tokio::task::spawn_blocking(move || write_atomically(&path, &text))
+                    .await
+                    .context("geocoding cache writer panicked")
+                    .and_then(|result| result)
+            }
+            Err(err) => Err(err),
+        };
+        if let Err(err) = result {
+            warn!(?err, path = %path.display(), "failed to write geocoding cache");
+        }
+    }
+}
+
+/// Writes to a temporary file first so a crash can't leave half a file.
+fn write_atomically(path: &Path, text: &str) -> anyhow::Result<()> {
+    let temporary = path.with_extension("toml.tmp");
+    fs::write(&temporary, text)
+        .with_context(|| format!("failed to write {}", temporary.display()))?;
+    fs::rename(&temporary, path).with_context(|| format!("failed to replace {}", path.display()))
+}
+
+/// Search results by provider, language and lowercased name, mirrored to
+/// a TOML file. Loaded on first use, and again if `cache_path` changes on a
+/// config reload.
+#[derive(Default)]
+struct PlaceCache {
+    path: Option<PathBuf>,
+    entries: HashMap<String, Vec<Place>>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct PlaceCacheFile {
+    #[serde(default)]
+    places: HashMap<String, Vec<Place>>,
+}
+
+impl PlaceCache {
+    fn get(&mut self, path: &Path, key: &str) -> Option<Vec<Place>> {
+        self.load(path);
+        self.entries.get(key).cloned()
+    }
+
+    /// Adds an entry; the file is rewritten by [`Geocoders::save_cache`]
+    /// once the lock is released.
+    fn insert(&mut self, path: &Path, key: String, places: Vec<Place>) {
+        self.load(path);
+        self.entries.insert(key, places);
+    }
+
+    fn load(&mut self, path: &Path) {
+        if self.path.as_deref() == Some(path) {
+            return;
+        }
+        self.path = Some(path.to_path_buf());
+        self.entries = match fs::read_to_string(path) {
+            Ok(text) => match toml::from_str::<PlaceCacheFile>(&text) {
+                Ok(file) => file.places,
+                Err(err) => {
+                    warn!(?err, path = %path.display(), "ignoring unreadable geocoding cache");
+                    HashMap::new()
+                }
+            },
+            Err(_) => HashMap::new(),
+        };
+    }
+
+    fn to_toml(&self) -> anyhow::Result<String> {
+        let file = PlaceCacheFile {
+            places: self.entries.clone(),
+        };
+        toml::to_string(&file).context("failed to serialize geocoding cache")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use axum::Router;
+    use axum::extract::Query;
+    use axum::routing::get;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn caches_only_searches_that_found_something() {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let handler = {
+            let requests = requests.clone();
+            move |Query(query): Query<HashMap<String, String>>| async move {
+                requests.fetch_add(1, Ordering::SeqCst);
+                match query["name"].as_str() {
+                    "Oslo" => {
+                        r#"{"results":[{"id":3143244,"name":"Oslo","latitude":59.91273,"longitude":10.74609,"country_code":"NO","admin1":"Oslo","country":"Norway"}],"generationtime_ms":0.6}"#
+                    }
+                    _ => r#"{"generationtime_ms":0.4}"#,
+                }
+            }
+        };
+        let app = Router::new().route("/v1/search", get(handler));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let geocoders = Geocoders {
+            http: Client::new(),
+            open_meteo_base_url: base_url,
+            nominatim_base_url: String::new(),
+            cache: Mutex::new(PlaceCache::default()),
+            saving: tokio::sync::Mutex::new(()),
+        };
+        let settings = GeocodingSettings {
+            provider: GeocoderKind::OpenMeteo,
+            cache_path: std::env::temp_dir().join(format!("geocoding-{}.toml", std::process::id())),
+        };
+        let resolve = |query| geocoders.resolve(&settings, Locale::default(), query);
+
+        assert!(resolve("Nowhere").await.unwrap().is_empty());
+        assert!(resolve("Nowhere").await.unwrap().is_empty());
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+        assert!(!settings.cache_path.exists());
+
+        assert_eq!(resolve("Oslo").await.unwrap()[0].label(), "Oslo, Norway");
+        assert_eq!(resolve("oslo").await.unwrap().len(), 1);
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+        let file: PlaceCacheFile =
+            toml::from_str(&fs::read_to_string(&settings.cache_path).unwrap()).unwrap();
+        fs::remove_file(&settings.cache_path).unwrap();
+        assert_eq!(file.places.len(), 1);
+        assert!(file.places.contains_key("open-meteo:en:oslo"));
+    }
+}
@@ -0,0 +1,96 @@
+use anyhow::Context;
+use futures::future::BoxFuture;
+use reqwest::Client;
+use serde::Deserialize;
+
+use super::{Geocoder, Place};
+
+/// Candidates returned per search; enough to list on the disambiguation page.
+const MAX_RESULTS: u32 = 10;
+
+/// OpenStreetMap's Nominatim. Its usage policy asks for an identifying user
+/// agent and at most one request a second, which the disk cache keeps well
+/// clear of.
+pub struct Nominatim {
+    pub(super) http: Client,
+    pub(super) base_url: String,
+    pub(super) language: &'static str,
+}
+
+impl Geocoder for Nominatim {
+    fn search(&self, name: &str) -> BoxFuture<'_, anyhow::Result<Vec<Place>>> {
+        let name = name.to_string();
+        Box::pin(async move { self.fetch_places(&name).await })
+    }
+}
+
+impl Nominatim {
+    async fn fetch_places(&self, name: &str) -> anyhow::Result<Vec<Place>> {
+        let results: Vec<SearchResult> = self
+            .http
+            .get(format!("{}/search", self.base_url))
+            .query(&[
+                ("q", name),
+                ("format", "jsonv2"),
+                ("addressdetails", "1"),
+                ("limit", &MAX_RESULTS.to_string()),
+                ("accept-language", self.language),
+            ])
+            .send()
+            .await
+            .context("failed to call Nominatim")?
+            .error_for_status()
+            .context("Nominatim returned an error")?
+            .json()
+            .await
+            .context("failed to decode Nominatim response")?;
+
+        results
+            .into_iter()
+            .map(|result| {
+                let address = result.address.unwrap_or_default();
+                Ok(Place {
+                    // Postal code matches have no name of their own
+                    name: result
+                        .name
+                        .filter(|name| !name.is_empty())
+                        .or(address.city)
+                        .or(address.town)
+                        .or(address.village)
+                        .or(address.postcode)
+                        .unwrap_or(result.display_name),
+                    region: address.state,
+                    country: address.country,
+                    latitude: result
+                        .lat
+                        .parse()
+                        .context("invalid latitude from Nominatim")?,
+                    longitude: result
+                        .lon
+                        .parse()
+                        .context("invalid longitude from Nominatim")?,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Coordinates come as strings.
+#[derive(Deserialize)]
+struct SearchResult {
+    lat: String,
+    lon: String,
+    name: Option<String>,
+    display_name: String,
+    address: Option<Address>,
+}
+
+#[derive(Default, Deserialize)]
+struct Address {
+    city: Option<String>,
+    town: Option<String>,
+    village: Option<String>,
+    postcode: Option<String>,
+    state: Option<String>,
+    country: Option<String>,
+}
@@ -0,0 +1,74 @@
+use anyhow::Context;
+use futures::future::BoxFuture;
+use reqwest::Client;
+use serde::Deserialize;
+
+use super::{Geocoder, Place};
+
+/// Candidates returned per search; enough to list on the disambiguation page.
+const MAX_RESULTS: u32 = 10;
+
+/// Open-Meteo's geocoding API, built on GeoNames. Matches place names and
+/// postal codes, largest places first.
+pub struct OpenMeteoGeocoding {
+    pub(super) http: Client,
+    pub(super) base_url: String,
+    pub(super) language: &'static str,
+}
+
+impl Geocoder for OpenMeteoGeocoding {
+    fn search(&self, name: &str) -> BoxFuture<'_, anyhow::Result<Vec<Place>>> {
+        let name = name.to_string();
+        Box::pin(async move { self.fetch_places(&name).await })
+    }
+}
+
+impl OpenMeteoGeocoding {
+    async fn fetch_places(&self, name: &str) -> anyhow::Result<Vec<Place>> {
+        let response: SearchResponse = self
+            .http
+            .get(format!("{}/v1/search", self.base_url))
+            .query(&[
+                ("name", name),
+                ("count", &MAX_RESULTS.to_string()),
+                ("language", self.language),
+                ("format", "json"),
+            ])
+            .send()
+            .await
+            .context("failed to call geocoding API")?
+            .error_for_status()
+            .context("geocoding API returned an error")?
+            .json()
+            .await
+            .context("failed to decode geocoding API response")?;
+
+        Ok(response
+            .results
+            .into_iter()
+            .map(|result| Place {
+                name: result.name,
+                region: result.admin1,
+                country: result.country,
+                latitude: result.latitude,
+                longitude: result.longitude,
+            })
+            .collect())
+    }
+}
+
+#[derive(Deserialize)]
+struct SearchResponse {
+    /// Missing altogether when nothing matched.
+    #[serde(default)]
+    results: Vec<SearchResult>,
+}
+
+#[derive(Deserialize)]
+struct SearchResult {
+    name: String,
+    latitude: f64,
+    longitude: f64,
+    admin1: Option<String>,
+    country: Option<String>,
+}
@@ -0,0 +1,387 @@
+//! "4° warmer than yesterday" and "above normal for October", from
+//! Open-Meteo's forecast (with `past_days`) and historical archive APIs.
+//! Yesterday's and today's temperatures change once a day and are cached per
+//! local date; the normal is cached per calendar month.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Context, bail};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use chrono_tz::Tz;
+use futures::future::try_join_all;
+use reqwest::Client;
+use serde::Deserialize;
+use tracing::warn;
+
+use crate::Coordinates;
+use crate::cache::RETRY_AFTER;
+use crate::provider::base_url;
+use crate::units::Units;
+
+/// Reference period for the climate normal, the current WMO standard.
+const NORMAL_YEARS: std::ops::RangeInclusive<i32> = 1991..=2020;
+
+/// Today's temperatures against yesterday and the long-term average, in the
+/// requested units.
+#[derive(Debug, Clone, Copy)]
+pub struct Comparison {
+    /// Today's forecast high minus yesterday's high.
+    pub vs_yesterday: f64,
+    /// Today's mean, `(max + min) / 2`, minus the normal for the month;
+    /// `None` while the normal isn't known yet.
+    pub vs_normal: Option<f64>,
+    /// Calendar month the normal is for, 1 to 12.
+    pub month: u32,
+}
+
+impl Comparison {
+    fn new(recent: Recent, normal: Option<f64>, month: u32) -> Self {
+        Self {
+            vs_yesterday: recent.today.max - recent.yesterday.max,
+            vs_normal: normal.map(|normal| recent.today.mean() - normal),
+            month,
+        }
+    }
+}
+
+/// Location rounded like the weather cache's, plus units.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct HistoryKey {
+    latitude: i32,
+    longitude: i32,
+    units: Units,
+}
+
+/// The climate normal is the same every year, so it only depends on the month.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct NormalKey {
+    location: HistoryKey,
+    month: u32,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Normal {
+    Known(f64),
+    /// The archive failed; not asked again before then.
+    RetryAt(DateTime<Utc>),
+}
+
+pub struct HistoryClient {
+    http: Client,
+    forecast_base_url: String,
+    archive_base_url: String,
+    /// Yesterday's and today's temperatures per location and the local date
+    /// they are for.
+    recent: Mutex<HashMap<HistoryKey, (NaiveDate, Recent)>>,
+    /// Shared with the tasks fetching them, which outlive a render that
+    /// gives up on them.
+    normals: Arc<Mutex<HashMap<NormalKey, Normal>>>,
+    /// Normals being fetched, so later renders don't start another fetch.
+    pending: Arc<Mutex<HashSet<NormalKey>>>,
+}
+
+impl HistoryClient {
+    pub fn new() -> Self {
+        Self {
+            http: Client::new(),
+            forecast_base_url: base_url("WEATHER_API_BASE_URL", "https://api.open-meteo.com"),
+            archive_base_url: base_url(
+                "ARCHIVE_API_BASE_URL",
+                "https://archive-api.open-meteo.com",
+            ),
+            recent: Mutex::new(HashMap::new()),
+            normals: Arc::new(Mutex::new(HashMap::new())),
+            pending: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    /// Compares `today` (a date in `timezone`) at `coords`. The normal is
+    /// fetched in its own task, so one that misses the render's deadline is
+    /// still there for the next; until then `vs_normal` is `None`.
+    pub async fn fetch(
+        &self,
+        coords: Coordinates,
+        units: Units,
+        timezone: Tz,
+        today: NaiveDate,
+    ) -> anyhow::Result<Comparison> {
+        let location = HistoryKey {
+            latitude: (coords.latitude * 100.0).round() as i32,
+            longitude: (coords.longitude * 100.0).round() as i32,
+            units,
+        };
+        let key = NormalKey {
+            location,
+            month: today.month(),
+        };
+
+        let (recent, normal) = tokio::join!(
+            self.recent(location, coords, units, timezone, today),
+            self.normal(key, coords, timezone),
+        );
+        Ok(Comparison::new(recent?, normal, today.month()))
+    }
+
+    async fn recent(
+        &self,
+        key: HistoryKey,
+        coords: Coordinates,
+        units: Units,
+        timezone: Tz,
+        today: NaiveDate,
+    ) -> anyhow::Result<Recent> {
+        if let Some((date, recent)) = self.recent.lock().unwrap().get(&key)
+            && *date == today
+        {
+            return Ok(*recent);
+        }
+        let recent = self.fetch_recent(coords, units, timezone).await?;
+        let mut entries = self.recent.lock().unwrap();
+        // Drop other locations' entries from earlier days
+        entries.retain(|_, (date, _)| *date == today);
+        entries.insert(key, (today, recent));
+        Ok(recent)
+    }
+
+    async fn normal(&self, key: NormalKey, coords: Coordinates, timezone: Tz) -> Option<f64> {
+        match self.normals.lock().unwrap().get(&key) {
+            Some(Normal::Known(normal)) => return Some(*normal),
+            Some(Normal::RetryAt(retry_at)) if Utc::now() < *retry_at => return None,
+            _ => {}
+        }
+        // Another render is already waiting on it
+        if !self.pending.lock().unwrap().insert(key) {
+            return None;
+        }
+
+        let (http, base_url) = (self.http.clone(), self.archive_base_url.clone());
+        let (normals, pending) = (self.normals.clone(), self.pending.clone());
+        let fetch = tokio::spawn(async move {
+            let result = fetch_normal(
+                &http,
+                &base_url,
+                coords,
+                key.location.units,
+                timezone,
+                key.month,
+            )
+            .await;
+            let normal = match result {
+                Ok(normal) => Normal::Known(normal),
+                Err(err) => {
+                    warn!(?err, "failed to fetch the climate normal");
+                    Normal::RetryAt(Utc::now() + RETRY_AFTER)
+                }
+            };
+            let mut normals = normals.lock().unwrap();
+            // Only this month's are asked for until the next
+            normals.retain(|other, _| other.month == key.month);
+            normals.insert(key, normal);
+            pending.lock().unwrap().remove(&key);
+            normal
+        });
+        match fetch.await {
+            Ok(Normal::Known(normal)) => Some(normal),
+            _ => None,
+        }
+    }
+
+    /// Yesterday's and today's highs and lows.
+    async fn fetch_recent(
+        &self,
+        coords: Coordinates,
+        units: Units,
+        timezone: Tz,
+    ) -> anyhow::Result<Recent> {
+        let url = format!(
+            "{}/v1/forecast?latitude={}&longitude={}&daily=temperature_2m_max,temperature_2m_min&past_days=1&forecast_days=1&temperature_unit={}&timezone={}",
+            self.forecast_base_url,
+            coords.latitude,
+            coords.longitude,
+            units.temperature_param(),
+            timezone.name(),
+        );
+        let response: DailyResponse = get(&self.http, url, "weather API").await?;
+
+        let days: Vec<Option<Temperatures>> = response.daily.days().map(|(_, day)| day).collect();
+        let [Some(yesterday), Some(today)] = days.as_slice() else {
+            bail!("weather API returned no highs and lows for yesterday and today");
+        };
+        Ok(Recent {
+            yesterday: *yesterday,
+            today: *today,
+        })
+    }
+}
+
+/// Average daily mean for `month` over the reference period, asking the
+/// archive for just that month of each year.
+async fn fetch_normal(
+    http: &Client,
+    base_url: &str,
+    coords: Coordinates,
+    units: Units,
+    timezone: Tz,
+    month: u32,
+) -> anyhow::Result<f64> {
+    let responses = try_join_all(month_ranges(month).map(|(start, end)| {
+        let url = format!(
+            "{base_url}/v1/archive?latitude={}&longitude={}&start_date={start}&end_date={end}&daily=temperature_2m_max,temperature_2m_min&temperature_unit={}&timezone={}",
+            coords.latitude,
+            coords.longitude,
+            units.temperature_param(),
+            timezone.name(),
+        );
+        get::<DailyResponse>(http, url, "archive API")
+    }))
+    .await?;
+
+    let days = responses.iter().flat_map(|response| response.daily.days());
+    monthly_normal(days, month)
+        .with_context(|| format!("archive API returned no days for month {month}"))
+}
+
+/// First and last day of `month` in each year of the reference period.
+fn month_ranges(month: u32) -> impl Iterator<Item = (NaiveDate, NaiveDate)> {
+    NORMAL_YEARS.filter_map(move |year| {
+        let start = NaiveDate::from_ymd_opt(year, month, 1)?;
+        let next = start.checked_add_months(chrono::Months::new(1))?;
+        Some((start, next - Duration::days(1)))
+    })
+}
+
+/// Mean of the daily means in `month`, skipping days without data.
+fn monthly_normal(
+    days: impl Iterator<Item = (NaiveDate, Option<Temperatures>)>,
+    month: u32,
+) -> Option<f64> {
+    let means: Vec<f64> = days
+        .filter(|(date, _)| date.month() == month)
+        .filter_map(|(_, day)| Some(day?.mean()))
+        .collect();
+    (!means.is_empty()).then(|| means.iter().sum::<f64>() / means.len() as f64)
+}
+
+async fn get<T: serde::de::DeserializeOwned>(
+    http: &Client,
+    url: String,
+    api: &str,
+) -> anyhow::Result<T> {
+    http.get(url)
+        .send()
+        .await
+        .with_context(|| format!("failed to call {api}"))?
+        .error_for_status()
+        .with_context(|| format!("{api} returned an error"))?
+        .json()
+        .await
+        .with_context(|| format!("failed to decode {api} response"))
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Temperatures {
+    max: f64,
+    min: f64,
+}
+
+impl Temperatures {
+    fn mean(self) -> f64 {
+        (self.max + self.min) / 2.0
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Recent {
+    yesterday: Temperatures,
+    today: Temperatures,
+}
+
+#[derive(Deserialize)]
+struct DailyResponse {
+    daily: Daily,
+}
+
+/// Days the model has no value for are `null`.
+#[derive(Deserialize)]
+struct Daily {
+    time: Vec<NaiveDate>,
+    temperature_2m_max: Vec<Option<f64>>,
+    temperature_2m_min: Vec<Option<f64>>,
+}
+
+impl Daily {
+    fn days(&self) -> impl Iterator<Item = (NaiveDate, Option<Temperatures>)> + '_ {
+        self.time
+            .iter()
+            .zip(&self.temperature_2m_max)
+            .zip(&self.temperature_2m_min)
+            .map(|((date, max), min)| {
+                let temperatures = max.zip(*min).map(|(max, min)| Temperatures { max, min });
+                (*date, temperatures)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::assert_close;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn temperatures(max: f64, min: f64) -> Option<Temperatures> {
+        Some(Temperatures { max, min })
+    }
+
+    #[test]
+    fn asks_for_one_month_per_year() {
+        let ranges: Vec<_> = month_ranges(2).collect();
+
+        assert_eq!(ranges.len(), 30);
+        assert_eq!(ranges[0], (date(1991, 2, 1), date(1991, 2, 28)));
+        // Leap years
+        assert_eq!(ranges[1], (date(1992, 2, 1), date(1992, 2, 29)));
+        assert_eq!(
+            month_ranges(12).last(),
+            Some((date(2020, 12, 1), date(2020, 12, 31)))
+        );
+    }
+
+    #[test]
+    fn normal_averages_the_months_daily_means() {
+        let days = [
+            (date(1991, 10, 1), temperatures(14.0, 6.0)),
+            (date(1991, 10, 2), None),
+            (date(1992, 10, 1), temperatures(12.0, 4.0)),
+            // Outside the month, as the archive returns whole ranges
+            (date(1992, 11, 1), temperatures(30.0, 20.0)),
+        ];
+
+        assert_close(monthly_normal(days.into_iter(), 10).unwrap(), 9.0);
+        assert!(monthly_normal(days.into_iter(), 3).is_none());
+    }
+
+    #[test]
+    fn compares_today_with_yesterday_and_the_normal() {
+        let recent = Recent {
+            yesterday: Temperatures {
+                max: 15.5,
+                min: 7.0,
+            },
+            today: Temperatures {
+                max: 12.0,
+                min: 4.0,
+            },
+        };
+
+        let comparison = Comparison::new(recent, Some(9.5), 10);
+        assert_close(comparison.vs_yesterday, -3.5);
+        assert_close(comparison.vs_normal.unwrap(), -1.5);
+        assert_eq!(comparison.month, 10);
+
+        assert!(Comparison::new(recent, None, 10).vs_normal.is_none());
+    }
+}
@@ -0,0 +1,764 @@
+use std::cmp::Ordering;
+
+use chrono::{DateTime, Datelike, Duration, TimeZone, Weekday};
+use serde::Deserialize;
+
+use crate::air_quality::Scale;
+use crate::condition::{Intensity, WeatherCondition};
+use crate::nowcast::{Outlook, WINDOW_HOURS};
+
+/// Language used for labels, day names, weather descriptions and the clock
+/// format on the dashboard.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    De,
+    Ja,
+    Vi,
+}
+
+/// Fixed UI strings that appear on the dashboard.
+#[derive(Clone, Copy, Debug)]
+pub enum Text {
+    Today,
+    Updated,
+    Offline,
+    Humidity,
+    FeelsLike,
+    Battery,
+    Charging,
+    BatteryUnavailable,
+    WeatherUnavailable,
+    NoPendingReviews,
+    Now,
+    Wind,
+    Gusts,
+    Pressure,
+    UvIndex,
+    Visibility,
+    DewPoint,
+    CloudCover,
+    Sunrise,
+    Sunset,
+    Daylight,
+    CivilTwilight,
+    Moonrise,
+    Moonset,
+    AirQualityUnavailable,
+    NoPollenData,
+    EuropeanAqi,
+    UsAqi,
+    Ozone,
+    Birch,
+    Grass,
+    Ragweed,
+    DataUnavailable,
+}
+
+impl Locale {
+    /// ISO 639-1 code, as geocoding services take it.
+    pub fn code(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::De => "de",
+            Locale::Ja => "ja",
+            Locale::Vi => "vi",
+        }
+    }
+
+    pub fn text(self, text: Text) -> &'static str {
+        match self {
+            Locale::En => match text {
+                Text::Today => "Today",
+                Text::Updated => "Updated",
+                Text::Offline => "offline",
+                Text::Humidity => "Hum",
+                Text::FeelsLike => "Feels",
+                Text::Battery => "Battery",
+                Text::Charging => "charging",
+                Text::BatteryUnavailable => "Battery status unavailable",
+                Text::WeatherUnavailable => "Weather unavailable",
+                Text::NoPendingReviews => "(no pending reviews)",
+                Text::Now => "Now",
+                Text::Wind => "Wind",
+                Text::Gusts => "Gusts",
+                Text::Pressure => "Pressure",
+                Text::UvIndex => "UV",
+                Text::Visibility => "Visibility",
+                Text::DewPoint => "Dew point",
+                Text::CloudCover => "Clouds",
+                Text::Sunrise => "Sunrise",
+                Text::Sunset => "Sunset",
+                Text::Daylight => "Daylight",
+                Text::CivilTwilight => "Civil twilight",
+                Text::Moonrise => "Moonrise",
+                Text::Moonset => "Moonset",
+                Text::AirQualityUnavailable => "Air quality unavailable",
+                Text::NoPollenData => "No pollen data for this area",
+                Text::EuropeanAqi => "EU AQI",
+                Text::UsAqi => "US AQI",
+                Text::Ozone => "Ozone",
+                Text::Birch => "Birch",
+                Text::Grass => "Grass",
+                Text::Ragweed => "Ragweed",
+                Text::DataUnavailable => "Data unavailable",
+            },
+            Locale::De => match text {
+                Text::Today => "Heute",
+                Text::Updated => "Aktualisiert",
+                Text::Offline => "offline",
+                Text::Humidity => "Feuchte",
+                Text::FeelsLike => "Gefühlt",
+                Text::Battery => "Akku",
+                Text::Charging => "lädt",
+                Text::BatteryUnavailable => "Akkustand unbekannt",
+                Text::WeatherUnavailable => "Wetter nicht verfügbar",
+                Text::NoPendingReviews => "(keine offenen Reviews)",
+                Text::Now => "Jetzt",
+                Text::Wind => "Wind",
+                Text::Gusts => "Böen",
+                Text::Pressure => "Luftdruck",
+                Text::UvIndex => "UV",
+                Text::Visibility => "Sicht",
+                Text::DewPoint => "Taupunkt",
+                Text::CloudCover => "Bewölkung",
+                Text::Sunrise => "Sonnenaufgang",
+                Text::Sunset => "Sonnenuntergang",
+                Text::Daylight => "Tageslicht",
+                Text::CivilTwilight => "Bürgerl. Dämmerung",
+                Text::Moonrise => "Mondaufgang",
+                Text::Moonset => "Monduntergang",
+                Text::AirQualityUnavailable => "Luftqualität nicht verfügbar",
+                Text::NoPollenData => "Keine Pollendaten für diese Region",
+                Text::EuropeanAqi => "EU-AQI",
+                Text::UsAqi => "US-AQI",
+                Text::Ozone => "Ozon",
+                Text::Birch => "Birke",
+                Text::Grass => "Gräser",
+                Text::Ragweed => "Ambrosia",
+                Text::DataUnavailable => "Daten nicht verfügbar",
+            },
+            Locale::Ja => match text {
+                Text::Today => "今日",
+                Text::Updated => "更新",
+                Text::Offline => "オフライン",
+                Text::Humidity => "湿度",
+                Text::FeelsLike => "体感",
+                Text::Battery => "バッテリー",
+                Text::Charging => "充電中",
+                Text::BatteryUnavailable => "バッテリー状態不明",
+                Text::WeatherUnavailable => "天気情報なし",
+                Text::NoPendingReviews => "(復習なし)",
+                Text::Now => "現在",
+                Text::Wind => "風",
+                Text::Gusts => "突風",
+                Text::Pressure => "気圧",
+                Text::UvIndex => "UV",
+                Text::Visibility => "視程",
+                Text::DewPoint => "露点",
+                Text::CloudCover => "雲量",
+                Text::Sunrise => "日の出",
+                Text::Sunset => "日の入り",
+                Text::Daylight => "日照時間",
+                Text::CivilTwilight => "市民薄明",
+                Text::Moonrise => "月の出",
+                Text::Moonset => "月の入り",
+                Text::AirQualityUnavailable => "大気質情報なし",
+                Text::NoPollenData => "この地域の花粉情報なし",
+                Text::EuropeanAqi => "欧州AQI",
+                Text::UsAqi => "米国AQI",
+                Text::Ozone => "オゾン",
+                Text::Birch => "シラカバ",
+                Text::Grass => "イネ科",
+                Text::Ragweed => "ブタクサ",
+                Text::DataUnavailable => "データなし",
+            },
+            Locale::Vi => match text {
+                Text::Today => "Hôm nay",
+                Text::Updated => "Cập nhật",
+                Text::Offline => "ngoại tuyến",
+                Text::Humidity => "Độ ẩm",
+                Text::FeelsLike => "Cảm giác",
+                Text::Battery => "Pin",
+                Text::Charging => "đang sạc",
+                Text::BatteryUnavailable => "Không rõ trạng thái pin",
+                Text::WeatherUnavailable => "Không có dữ liệu thời tiết",
+                Text::NoPendingReviews => "(không có bài ôn)",
+                Text::Now => "Bây giờ",
+                Text::Wind => "Gió",
+                Text::Gusts => "Gió giật",
+                Text::Pressure => "Áp suất",
+                Text::UvIndex => "UV",
+                Text::Visibility => "Tầm nhìn",
+                Text::DewPoint => "Điểm sương",
+                Text::CloudCover => "Mây",
+                Text::Sunrise => "Mặt trời mọc",
+                Text::Sunset => "Mặt trời lặn",
+                Text::Daylight => "Thời gian ban ngày",
+                Text::CivilTwilight => "Chạng vạng",
+                Text::Moonrise => "Trăng mọc",
+                Text::Moonset => "Trăng lặn",
+                Text::AirQualityUnavailable => "Không có dữ liệu chất lượng không khí",
+                Text::NoPollenData => "Không có dữ liệu phấn hoa cho khu vực này",
+                Text::EuropeanAqi => "AQI châu Âu",
+                Text::UsAqi => "AQI Mỹ",
+                Text::Ozone => "Ozone",
+                Text::Birch => "Bạch dương",
+                Text::Grass => "Cỏ",
+                Text::Ragweed => "Cỏ phấn hương",
+                Text::DataUnavailable => "Không có dữ liệu",
+            },
+        }
+    }
+
+    pub fn day_name(self, day: Weekday) -> &'static str {
+        let index = day.num_days_from_monday() as usize;
+        let names = match self {
+            Locale::En => [
+                "Monday",
+                "Tuesday",
+                "Wednesday",
+                "Thursday",
+                "Friday",
+                "Saturday",
+                "Sunday",
+            ],
+            Locale::De => [
+                "Montag",
+                "Dienstag",
+                "Mittwoch",
+                "Donnerstag",
+                "Freitag",
+                "Samstag",
+                "Sonntag",
+            ],
+            Locale::Ja => [
+                "月曜日",
+                "火曜日",
+                "水曜日",
+                "木曜日",
+                "金曜日",
+                "土曜日",
+                "日曜日",
+            ],
+            Locale::Vi => [
+                "Thứ Hai",
+                "Thứ Ba",
+                "Thứ Tư",
+                "Thứ Năm",
+                "Thứ Sáu",
+                "Thứ Bảy",
+                "Chủ Nhật",
+            ],
+        };
+        names[index]
+    }
+
+    pub fn short_day_name(self, day: Weekday) -> &'static str {
+        let index = day.num_days_from_monday() as usize;
+        let names = match self {
+            Locale::En => ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"],
+            Locale::De => ["Mo", "Di", "Mi", "Do", "Fr", "Sa", "So"],
+            Locale::Ja => ["月", "火", "水", "木", "金", "土", "日"],
+            Locale::Vi => ["T2", "T3", "T4", "T5", "T6", "T7", "CN"],
+        };
+        names[index]
+    }
+
+    /// Eight-point compass abbreviation for a bearing in degrees.
+    pub fn compass_point(self, degrees: f64) -> &'static str {
+        let index = ((degrees.rem_euclid(360.0) / 45.0).round() as usize) % 8;
+        let points = match self {
+            Locale::En => ["N", "NE", "E", "SE", "S", "SW", "W", "NW"],
+            Locale::De => ["N", "NO", "O", "SO", "S", "SW", "W", "NW"],
+            Locale::Ja => ["北", "北東", "東", "南東", "南", "南西", "西", "北西"],
+            Locale::Vi => ["B", "ĐB", "Đ", "ĐN", "N", "TN", "T", "TB"],
+        };
+        points[index]
+    }
+
+    fn uses_24h_clock(self) -> bool {
+        !matches!(self, Locale::En)
+    }
+
+    /// Time of day in the locale's clock, e.g. "03:00 PM" or "15:00".
+    pub fn format_time<Tz: TimeZone>(self, ts: &DateTime<Tz>) -> String
+    where
+        Tz::Offset: std::fmt::Display,
+    {
+        if self.uses_24h_clock() {
+            ts.format("%H:%M").to_string()
+        } else {
+            ts.format("%I:%M %p").to_string()
+        }
+    }
+
+    /// Short hour label for chart axes, e.g. "6 PM" or "18:00".
+    pub fn format_hour<Tz: TimeZone>(self, ts: &DateTime<Tz>) -> String
+    where
+        Tz::Offset: std::fmt::Display,
+    {
+        if self.uses_24h_clock() {
+            ts.format("%H:%M").to_string()
+        } else {
+            ts.format("%-I %p").to_string()
+        }
+    }
+
+    /// ISO date followed by the locale's time of day.
+    pub fn format_datetime<Tz: TimeZone>(self, ts: &DateTime<Tz>) -> String
+    where
+        Tz::Offset: std::fmt::Display,
+    {
+        format!("{} {}", ts.format("%Y-%m-%d"), self.format_time(ts))
+    }
+
+    /// How long ago something happened, e.g. "3h ago".
+    pub fn format_age(self, age: Duration) -> String {
+        let (value, unit) = if age < Duration::hours(1) {
+            (age.num_minutes().max(0), 0)
+        } else if age < Duration::days(1) {
+            (age.num_hours(), 1)
+        } else {
+            (age.num_days(), 2)
+        };
+
+        match self {
+            Locale::En => format!("{value}{} ago", ["m", "h", "d"][unit]),
+            Locale::De => format!("vor {value} {}", ["Min.", "Std.", "Tagen"][unit]),
+            Locale::Ja => format!("{value}{}前", ["分", "時間", "日"][unit]),
+            Locale::Vi => format!("{value} {} trước", ["phút", "giờ", "ngày"][unit]),
+        }
+    }
+
+    /// End of an alert such as "until Sat 06:00 PM" or "bis Sa 18:00".
+    pub fn format_until<Tz: TimeZone>(self, ts: &DateTime<Tz>) -> String
+    where
+        Tz::Offset: std::fmt::Display,
+    {
+        let day = self.short_day_name(ts.weekday());
+        let time = self.format_time(ts);
+        match self {
+            Locale::En => format!("until {day} {time}"),
+            Locale::De => format!("bis {day} {time}"),
+            Locale::Ja => format!("{day} {time}まで"),
+            Locale::Vi => format!("đến {time} {day}"),
+        }
+    }
+
+    /// Nowcast sentence such as "Rain starting in ~25 min, stopping around
+    /// 02:30 PM" or "No rain for the next 2 hours".
+    pub fn nowcast_summary(self, outlook: &Outlook) -> String {
+        let hours = WINDOW_HOURS;
+        match (self, outlook) {
+            (Locale::En, Outlook::Dry) => format!("No rain for the next {hours} hours"),
+            (Locale::En, Outlook::Starting { minutes, stops_at }) => match stops_at {
+                Some(stop) => format!(
+                    "Rain starting in ~{minutes} min, stopping around {}",
+                    self.format_time(stop)
+                ),
+                None => format!("Rain starting in ~{minutes} min"),
+            },
+            (Locale::En, Outlook::Ongoing { stops_at }) => match stops_at {
+                Some(stop) => format!("Rain stopping around {}", self.format_time(stop)),
+                None => format!("Rain for the next {hours} hours"),
+            },
+            (Locale::De, Outlook::Dry) => {
+                format!("Kein Regen in den nächsten {hours} Stunden")
+            }
+            (Locale::De, Outlook::Starting { minutes, stops_at }) => match stops_at {
+                Some(stop) => format!(
+                    "Regen in ca. {minutes} Min., endet gegen {}",
+                    self.format_time(stop)
+                ),
+                None => format!("Regen in ca. {minutes} Min."),
+            },
+            (Locale::De, Outlook::Ongoing { stops_at }) => match stops_at {
+                Some(stop) => format!("Regen endet gegen {}", self.format_time(stop)),
+                None => format!("Regen in den nächsten {hours} Stunden"),
+            },
+            (Locale::Ja, Outlook::Dry) => format!("今後{hours}時間は雨の予報なし"),
+            (Locale::Ja, Outlook::Starting { minutes, stops_at }) => match stops_at {
+                Some(stop) => format!(
+                    "約{minutes}分後に雨、{}頃にやむ見込み",
+                    self.format_time(stop)
+                ),
+                None => format!("約{minutes}分後に雨"),
+            },
+            (Locale::Ja, Outlook::Ongoing { stops_at }) => match stops_at {
+                Some(stop) => format!("{}頃に雨がやむ見込み", self.format_time(stop)),
+                None => format!("今後{hours}時間は雨が続く見込み"),
+            },
+            (Locale::Vi, Outlook::Dry) => format!("Không mưa trong {hours} giờ tới"),
+            (Locale::Vi, Outlook::Starting { minutes, stops_at }) => match stops_at {
+                Some(stop) => format!(
+                    "Mưa sau khoảng {minutes} phút, tạnh khoảng {}",
+                    self.format_time(stop)
+                ),
+                None => format!("Mưa sau khoảng {minutes} phút"),
+            },
+            (Locale::Vi, Outlook::Ongoing { stops_at }) => match stops_at {
+                Some(stop) => format!("Mưa tạnh khoảng {}", self.format_time(stop)),
+                None => format!("Mưa trong {hours} giờ tới"),
+            },
+        }
+    }
+
+    /// Today's high against yesterday's, e.g. "4° warmer than yesterday".
+    /// `degrees` is the rounded difference.
+    pub fn compare_yesterday(self, degrees: i64) -> String {
+        let amount = degrees.abs();
+        match (self, degrees.signum()) {
+            (Locale::En, 0) => "Similar to yesterday".to_string(),
+            (Locale::En, 1) => format!("{amount}° warmer than yesterday"),
+            (Locale::En, _) => format!("{amount}° cooler than yesterday"),
+            (Locale::De, 0) => "Ähnlich wie gestern".to_string(),
+            (Locale::De, 1) => format!("{amount}° wärmer als gestern"),
+            (Locale::De, _) => format!("{amount}° kühler als gestern"),
+            (Locale::Ja, 0) => "昨日とほぼ同じ".to_string(),
+            (Locale::Ja, 1) => format!("昨日より{amount}°高い"),
+            (Locale::Ja, _) => format!("昨日より{amount}°低い"),
+            (Locale::Vi, 0) => "Tương tự hôm qua".to_string(),
+            (Locale::Vi, 1) => format!("Ấm hơn hôm qua {amount}°"),
+            (Locale::Vi, _) => format!("Mát hơn hôm qua {amount}°"),
+        }
+    }
+
+    /// Today against the climate normal for `month` (1 to 12), e.g. "Above
+    /// normal for October". `ordering` is today relative to the normal.
+    pub fn compare_normal(self, ordering: Ordering, month: u32) -> String {
+        const EN: [&str; 12] = [
+            "January",
+            "February",
+            "March",
+            "April",
+            "May",
+            "June",
+            "July",
+            "August",
+            "September",
+            "October",
+            "November",
+            "December",
+        ];
+        const DE: [&str; 12] = [
+            "Januar",
+            "Februar",
+            "März",
+            "April",
+            "Mai",
+            "Juni",
+            "Juli",
+            "August",
+            "September",
+            "Oktober",
+            "November",
+            "Dezember",
+        ];
+        let index = (month.clamp(1, 12) - 1) as usize;
+        match (self, ordering) {
+            (Locale::En, Ordering::Greater) => format!("Above normal for {}", EN[index]),
+            (Locale::En, Ordering::Less) => format!("Below normal for {}", EN[index]),
+            (Locale::En, Ordering::Equal) => format!("Near normal for {}", EN[index]),
+            (Locale::De, Ordering::Greater) => format!("Wärmer als im {} üblich", DE[index]),
+            (Locale::De, Ordering::Less) => format!("Kühler als im {} üblich", DE[index]),
+            (Locale::De, Ordering::Equal) => format!("Normal für {}", DE[index]),
+            (Locale::Ja, Ordering::Greater) => format!("{month}月の平年より高め"),
+            (Locale::Ja, Ordering::Less) => format!("{month}月の平年より低め"),
+            (Locale::Ja, Ordering::Equal) => format!("{month}月の平年並み"),
+            (Locale::Vi, Ordering::Greater) => format!("Cao hơn mức thường của tháng {month}"),
+            (Locale::Vi, Ordering::Less) => format!("Thấp hơn mức thường của tháng {month}"),
+            (Locale::Vi, Ordering::Equal) => format!("Gần mức thường của tháng {month}"),
+        }
+    }
+
+    /// Day length such as "11h 42m".
+    pub fn format_day_length(self, length: Duration) -> String {
+        let (hours, minutes) = (length.num_hours(), length.num_minutes() % 60);
+        match self {
+            Locale::En | Locale::Vi => format!("{hours}h {minutes:02}m"),
+            Locale::De => format!("{hours} Std. {minutes:02} Min."),
+            Locale::Ja => format!("{hours}時間{minutes:02}分"),
+        }
+    }
+
+    /// Signed change in day length such as "+2m 13s" or "−1m 05s".
+    pub fn format_day_length_change(self, change: Duration) -> String {
+        let sign = if change < Duration::zero() {
+            '−'
+        } else {
+            '+'
+        };
+        let seconds = change.num_seconds().abs();
+        let (minutes, seconds) = (seconds / 60, seconds % 60);
+        match self {
+            Locale::En | Locale::Vi => format!("{sign}{minutes}m {seconds:02}s"),
+            Locale::De => format!("{sign}{minutes} Min. {seconds:02} s"),
+            Locale::Ja => format!("{sign}{minutes}分{seconds:02}秒"),
+        }
+    }
+
+    /// Name of one of the eight moon phases, 0 being new moon.
+    pub fn moon_phase(self, index: usize) -> &'static str {
+        let names = match self {
+            Locale::En => [
+                "New moon",
+                "Waxing crescent",
+                "First quarter",
+                "Waxing gibbous",
+                "Full moon",
+                "Waning gibbous",
+                "Last quarter",
+                "Waning crescent",
+            ],
+            Locale::De => [
+                "Neumond",
+                "Zunehmende Sichel",
+                "Erstes Viertel",
+                "Zunehmender Mond",
+                "Vollmond",
+                "Abnehmender Mond",
+                "Letztes Viertel",
+                "Abnehmende Sichel",
+            ],
+            Locale::Ja => [
+                "新月",
+                "三日月",
+                "上弦の月",
+                "十三夜月",
+                "満月",
+                "寝待月",
+                "下弦の月",
+                "有明月",
+            ],
+            Locale::Vi => [
+                "Trăng non",
+                "Trăng lưỡi liềm đầu tháng",
+                "Trăng thượng huyền",
+                "Trăng khuyết đầu tháng",
+                "Trăng tròn",
+                "Trăng khuyết cuối tháng",
+                "Trăng hạ huyền",
+                "Trăng lưỡi liềm cuối tháng",
+            ],
+        };
+        names[index % 8]
+    }
+
+    /// Name of a level on an air quality or pollen scale, 0 being the best.
+    pub fn air_quality_level(self, scale: Scale, level: usize) -> &'static str {
+        let names: &[&str] = match (self, scale) {
+            (Locale::En, Scale::European) => &[
+                "Good",
+                "Fair",
+                "Moderate",
+                "Poor",
+                "Very poor",
+                "Extremely poor",
+            ],
+            (Locale::En, Scale::Us) => &[
+                "Good",
+                "Moderate",
+                "Unhealthy for some",
+                "Unhealthy",
+                "Very unhealthy",
+                "Hazardous",
+            ],
+            (Locale::En, Scale::Pollen) => &["None", "Low", "Moderate", "High", "Very high"],
+            (Locale::De, Scale::European) => &[
+                "Gut",
+                "Mittelmäßig",
+                "Mäßig",
+                "Schlecht",
+                "Sehr schlecht",
+                "Extrem schlecht",
+            ],
+            (Locale::De, Scale::Us) => &[
+                "Gut",
+                "Mäßig",
+                "Ungesund für Empfindliche",
+                "Ungesund",
+                "Sehr ungesund",
+                "Gefährlich",
+            ],
+            (Locale::De, Scale::Pollen) => &["Keine", "Gering", "Mäßig", "Hoch", "Sehr hoch"],
+            (Locale::Ja, Scale::European) => &[
+                "良い",
+                "まずまず",
+                "普通",
+                "悪い",
+                "非常に悪い",
+                "極めて悪い",
+            ],
+            (Locale::Ja, Scale::Us) => &[
+                "良い",
+                "普通",
+                "敏感な人に不健康",
+                "不健康",
+                "非常に不健康",
+                "危険",
+            ],
+            (Locale::Ja, Scale::Pollen) => &["なし", "少ない", "やや多い", "多い", "非常に多い"],
+            (Locale::Vi, Scale::European) => {
+                &["Tốt", "Khá", "Trung bình", "Kém", "Rất kém", "Cực kém"]
+            }
+            (Locale::Vi, Scale::Us) => &[
+                "Tốt",
+                "Trung bình",
+                "Kém với nhóm nhạy cảm",
+                "Kém",
+                "Rất kém",
+                "Nguy hại",
+            ],
+            (Locale::Vi, Scale::Pollen) => &["Không có", "Thấp", "Trung bình", "Cao", "Rất cao"],
+        };
+        names[level.min(names.len() - 1)]
+    }
+
+    /// Localized label for a condition, e.g. "Light rain" or "Starker Regen".
+    pub fn weather_description(self, condition: WeatherCondition) -> &'static str {
+        use Intensity::*;
+        use WeatherCondition::*;
+
+        match self {
+            Locale::En => match condition {
+                Clear => "Clear sky",
+                MainlyClear => "Mainly clear",
+                PartlyCloudy => "Partly cloudy",
+                Overcast => "Overcast",
+                Fog => "Fog",
+                RimeFog => "Rime fog",
+                Drizzle(Light) => "Light drizzle",
+                Drizzle(Moderate) => "Drizzle",
+                Drizzle(Heavy) => "Dense drizzle",
+                FreezingDrizzle(Light) => "Light freezing drizzle",
+                FreezingDrizzle(Moderate) => "Freezing drizzle",
+                FreezingDrizzle(Heavy) => "Dense freezing drizzle",
+                Rain(Light) => "Light rain",
+                Rain(Moderate) => "Rain",
+                Rain(Heavy) => "Heavy rain",
+                FreezingRain(Light) => "Light freezing rain",
+                FreezingRain(Moderate) => "Freezing rain",
+                FreezingRain(Heavy) => "Heavy freezing rain",
+                Snowfall(Light) => "Light snow",
+                Snowfall(Moderate) => "Snow",
+                Snowfall(Heavy) => "Heavy snow",
+                SnowGrains => "Snow grains",
+                RainShowers(Light) => "Light rain showers",
+                RainShowers(Moderate) => "Rain showers",
+                RainShowers(Heavy) => "Violent rain showers",
+                SnowShowers(Light) => "Light snow showers",
+                SnowShowers(Moderate) => "Snow showers",
+                SnowShowers(Heavy) => "Heavy snow showers",
+                Thunderstorm => "Thunderstorm",
+                ThunderstormWithHail(Light | Moderate) => "Thunderstorm with hail",
+                ThunderstormWithHail(Heavy) => "Thunderstorm with heavy hail",
+                Unknown => "Unknown",
+            },
+            Locale::De => match condition {
+                Clear => "Klar",
+                MainlyClear => "Überwiegend klar",
+                PartlyCloudy => "Teilweise bewölkt",
+                Overcast => "Bedeckt",
+                Fog => "Nebel",
+                RimeFog => "Nebel mit Reifbildung",
+                Drizzle(Light) => "Leichter Nieselregen",
+                Drizzle(Moderate) => "Nieselregen",
+                Drizzle(Heavy) => "Starker Nieselregen",
+                FreezingDrizzle(Light) => "Leichter gefrierender Nieselregen",
+                FreezingDrizzle(Moderate) => "Gefrierender Nieselregen",
+                FreezingDrizzle(Heavy) => "Starker gefrierender Nieselregen",
+                Rain(Light) => "Leichter Regen",
+                Rain(Moderate) => "Regen",
+                Rain(Heavy) => "Starker Regen",
+                FreezingRain(Light) => "Leichter gefrierender Regen",
+                FreezingRain(Moderate) => "Gefrierender Regen",
+                FreezingRain(Heavy) => "Starker gefrierender Regen",
+                Snowfall(Light) => "Leichter Schneefall",
+                Snowfall(Moderate) => "Schneefall",
+                Snowfall(Heavy) => "Starker Schneefall",
+                SnowGrains => "Schneegriesel",
+                RainShowers(Light) => "Leichte Regenschauer",
+                RainShowers(Moderate) => "Regenschauer",
+                RainShowers(Heavy) => "Heftige Regenschauer",
+                SnowShowers(Light) => "Leichte Schneeschauer",
+                SnowShowers(Moderate) => "Schneeschauer",
+                SnowShowers(Heavy) => "Starke Schneeschauer",
+                Thunderstorm => "Gewitter",
+                ThunderstormWithHail(Light | Moderate) => "Gewitter mit Hagel",
+                ThunderstormWithHail(Heavy) => "Gewitter mit starkem Hagel",
+                Unknown => "Unbekannt",
+            },
+            Locale::Ja => match condition {
+                Clear => "快晴",
+                MainlyClear => "晴れ",
+                PartlyCloudy => "晴れ時々曇り",
+                Overcast => "曇り",
+                Fog => "霧",
+                RimeFog => "着氷性の霧",
+                Drizzle(Light) => "弱い霧雨",
+                Drizzle(Moderate) => "霧雨",
+                Drizzle(Heavy) => "強い霧雨",
+                FreezingDrizzle(Light) => "弱い着氷性の霧雨",
+                FreezingDrizzle(Moderate) => "着氷性の霧雨",
+                FreezingDrizzle(Heavy) => "強い着氷性の霧雨",
+                Rain(Light) => "小雨",
+                Rain(Moderate) => "雨",
+                Rain(Heavy) => "大雨",
+                FreezingRain(Light) => "弱い着氷性の雨",
+                FreezingRain(Moderate) => "着氷性の雨",
+                FreezingRain(Heavy) => "強い着氷性の雨",
+                Snowfall(Light) => "小雪",
+                Snowfall(Moderate) => "雪",
+                Snowfall(Heavy) => "大雪",
+                SnowGrains => "霧雪",
+                RainShowers(Light) => "弱いにわか雨",
+                RainShowers(Moderate) => "にわか雨",
+                RainShowers(Heavy) => "激しいにわか雨",
+                SnowShowers(Light) => "弱いにわか雪",
+                SnowShowers(Moderate) => "にわか雪",
+                SnowShowers(Heavy) => "強いにわか雪",
+                Thunderstorm => "雷雨",
+                ThunderstormWithHail(Light | Moderate) => "ひょうを伴う雷雨",
+                ThunderstormWithHail(Heavy) => "激しいひょうを伴う雷雨",
+                Unknown => "不明",
+            },
+            Locale::Vi => match condition {
+                Clear => "Trời quang",
+                MainlyClear => "Ít mây",
+                PartlyCloudy => "Có mây rải rác",
+                Overcast => "U ám",
+                Fog => "Sương mù",
+                RimeFog => "Sương mù đóng băng",
+                Drizzle(Light) => "Mưa phùn nhẹ",
+                Drizzle(Moderate) => "Mưa phùn",
+                Drizzle(Heavy) => "Mưa phùn dày",
+                FreezingDrizzle(Light) => "Mưa phùn băng giá nhẹ",
+                FreezingDrizzle(Moderate) => "Mưa phùn băng giá",
+                FreezingDrizzle(Heavy) => "Mưa phùn băng giá dày",
+                Rain(Light) => "Mưa nhẹ",
+                Rain(Moderate) => "Mưa",
+                Rain(Heavy) => "Mưa to",
+                FreezingRain(Light) => "Mưa băng giá nhẹ",
+                FreezingRain(Moderate) => "Mưa băng giá",
+                FreezingRain(Heavy) => "Mưa băng giá to",
+                Snowfall(Light) => "Tuyết nhẹ",
+                Snowfall(Moderate) => "Tuyết rơi",
+                Snowfall(Heavy) => "Tuyết dày",
+                SnowGrains => "Tuyết hạt",
+                RainShowers(Light) => "Mưa rào nhẹ",
+                RainShowers(Moderate) => "Mưa rào",
+                RainShowers(Heavy) => "Mưa rào rất to",
+                SnowShowers(Light) => "Mưa tuyết nhẹ",
+                SnowShowers(Moderate) => "Mưa tuyết",
+                SnowShowers(Heavy) => "Mưa tuyết dày",
+                Thunderstorm => "Dông",
+                ThunderstormWithHail(Light | Moderate) => "Dông kèm mưa đá",
+                ThunderstormWithHail(Heavy) => "Dông kèm mưa đá lớn",
+                Unknown => "Không rõ",
+            },
+        }
+    }
+}
@@ -1,196 +1,220 @@
-use std::{env, io::Cursor, net::SocketAddr, sync::Arc};
+use std::{
+    env,
+    io::Cursor,
+    net::SocketAddr,
+    path::PathBuf,
+    sync::{Arc, RwLock},
+};
 
 use anyhow::{Context, anyhow};
 use askama::Template;
 use askama_web::WebTemplate;
 use axum::{
     Router,
-    extract::{Path, Query, State},
+    body::Bytes,
+    extract::{Path, Query, RawQuery, State},
     http::{HeaderMap, header},
     response::{IntoResponse, Response},
     routing::get,
 };
-use chrono::{DateTime, Duration, Local, TimeZone, Utc};
-use image::{DynamicImage, ImageBuffer, ImageFormat, Luma};
-use reqwest::Client;
+use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::Tz;
+use image::{DynamicImage, ImageBuffer, Luma};
+use percent_encoding::{NON_ALPHANUMERIC, utf8_percent_encode};
 use serde::{
     Deserialize,
     de::{self, Deserializer, Unexpected},
 };
+use sha2::{Digest, Sha256};
 use tokio::signal;
 use tower_http::services::ServeDir;
 use tracing::{error, info};
 use tracing_subscriber::EnvFilter;
 use typst::foundations::{Array, Dict, IntoValue, Str, Value};
 
+mod air_quality;
+mod alerts;
+mod astronomy;
+mod cache;
+mod condition;
+mod config;
+mod device;
+mod dither;
+mod geocoding;
+mod history;
+mod locale;
+mod nowcast;
+mod prerender;
+mod provider;
 mod render;
+mod source;
+mod units;
 mod wanikani;
+mod widget;
+use crate::air_quality::{AirQuality, AirQualityClient};
+use crate::alerts::{Alert, AlertSources};
+use crate::cache::{CachedWeather, WeatherCache};
+use crate::condition::WeatherCondition;
+use crate::config::{DashboardConfig, NamedLocation, WidgetSlot};
+use crate::device::{DeviceProfile, OutputFormat, Rotation};
+use crate::dither::{Dither, encode_png, quantize};
+use crate::geocoding::{Geocoders, Place};
+use crate::history::{Comparison, HistoryClient};
+use crate::locale::{Locale, Text};
+use crate::prerender::{Battery, PrerenderKey, Prerendered};
+use crate::provider::{WeatherProviders, nautical_timezone};
 use crate::render::{get_template, render_widget};
+use crate::source::{AlertsSource, DataSource, FetchContext, Fetched, Source};
+use crate::units::Units;
 use crate::wanikani::{WanikaniClient, WanikaniKanji};
+use crate::widget::WidgetContext;
 
-const DEFAULT_KINDLE_WIDTH: u32 = 1072;
-const DEFAULT_KINDLE_HEIGHT: u32 = 1448;
 const DASHBOARD_TEMPLATE: &str = "dashboard.typ";
+/// Alerts shown at once; the rest are dropped, least severe first.
+const MAX_ALERT_BANNERS: usize = 2;
 
-#[derive(Clone)]
 struct AppState {
-    client: WeatherClient,
-    config: DashboardConfig,
+    providers: WeatherProviders,
+    cache: Arc<WeatherCache>,
+    config_path: PathBuf,
+    config: RwLock<Arc<DashboardConfig>>,
     wanikani: WanikaniClient,
+    air_quality: AirQualityClient,
+    alerts: AlertSources,
+    history: HistoryClient,
+    geocoders: Geocoders,
+    prerendered: Prerendered,
 }
 
-#[derive(Clone)]
-struct DashboardConfig {
-    latitude: f64,
-    longitude: f64,
-    width: u32,
-    height: u32,
+impl AppState {
+    /// Snapshot of the current config. Handlers take one at the start so a
+    /// reload mid-request can't mix values from two different files.
+    fn config(&self) -> Arc<DashboardConfig> {
+        self.config.read().unwrap().clone()
+    }
+
+    fn reload_config(&self) -> anyhow::Result<()> {
+        let config = DashboardConfig::load(&self.config_path)?;
+        log_config(&self.config_path, &config);
+        *self.config.write().unwrap() = Arc::new(config);
+        Ok(())
+    }
 }
 
-impl DashboardConfig {
-    fn from_env() -> Self {
-        let latitude = env::var("DEFAULT_LATITUDE")
-            .ok()
-            .and_then(|v| v.parse().ok())
-            .unwrap_or(40.7128);
-        let longitude = env::var("DEFAULT_LONGITUDE")
-            .ok()
-            .and_then(|v| v.parse().ok())
-            .unwrap_or(-74.0060);
-        let width = env::var("DASHBOARD_WIDTH")
-            .ok()
-            .and_then(|v| v.parse().ok())
-            .unwrap_or(DEFAULT_KINDLE_WIDTH);
-        let height = env::var("DASHBOARD_HEIGHT")
-            .ok()
-            .and_then(|v| v.parse().ok())
-            .unwrap_or(DEFAULT_KINDLE_HEIGHT);
+/// What to ask the weather API for, besides the location. Part of the cache
+/// key since it changes the shape and units of the response.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct WeatherQuery {
+    units: Units,
+    forecast_days: u8,
+}
 
-        Self {
-            latitude,
-            longitude,
-            width,
-            height,
-        }
-    }
+/// Everything fetched for one render. Sources that failed are `None` or
+/// empty, and their widgets draw placeholders.
+#[derive(Default)]
+struct DashboardData {
+    weather: Option<CachedWeather>,
+    air_quality: Option<AirQuality>,
+    alerts: Vec<Alert>,
+    comparison: Option<Comparison>,
+    /// Compact cards of a location set, in config order.
+    locations: Vec<SecondaryLocation>,
+    kanji: Vec<WanikaniKanji>,
+    /// Sources that failed or ran out of time; widgets built from them are
+    /// tagged as unavailable.
+    unavailable: Vec<Source>,
+}
 
-    fn coordinates(&self, path: &CoordinatesPath) -> Coordinates {
-        Coordinates {
-            latitude: path.latitude,
-            longitude: path.longitude,
+impl DashboardData {
+    fn store(&mut self, fetched: Fetched) {
+        match fetched {
+            Fetched::Weather(weather) => self.weather = Some(weather),
+            Fetched::History(comparison) => self.comparison = Some(comparison),
+            Fetched::AirQuality(air_quality) => self.air_quality = Some(air_quality),
+            Fetched::Locations(locations) => self.locations = locations,
+            Fetched::Kanji(kanji) => self.kanji = kanji,
+            Fetched::Alerts(alerts) => self.alerts = alerts,
         }
     }
+}
 
-    fn dimensions(&self, params: &RenderParams) -> (u32, u32) {
-        let width = params.width.unwrap_or(self.width).max(1);
-        let height = params.height.unwrap_or(self.height).max(1);
-        (width, height)
-    }
+/// What a dashboard URL renders, besides the device.
+#[derive(Clone, Debug)]
+enum RenderTarget {
+    /// `/render/{latitude}/{longitude}`
+    Location(Coordinates),
+    /// `/dashboard/{id}`
+    LocationSet(String),
 }
 
+/// An encoded dashboard image and its ETag, the SHA-256 of its bytes, which
+/// stays the same across restarts and builds.
 #[derive(Clone)]
-struct WeatherClient {
-    http: Client,
+struct RenderedImage {
+    bytes: Bytes,
+    content_type: &'static str,
+    etag: String,
+    /// When the weather it shows was fetched; unset without any, since the
+    /// render time would claim a change the ETag doesn't show.
+    modified: Option<DateTime<Utc>>,
 }
 
-impl WeatherClient {
-    fn new() -> Self {
+impl RenderedImage {
+    fn new(bytes: Vec<u8>, content_type: &'static str, modified: Option<DateTime<Utc>>) -> Self {
         Self {
-            http: Client::new(),
+            etag: format!("\"{:x}\"", Sha256::digest(&bytes)),
+            bytes: Bytes::from(bytes),
+            content_type,
+            modified,
         }
     }
 
-    async fn fetch_weather_data(&self, coords: Coordinates) -> anyhow::Result<WeatherData> {
-        let url = format!(
-            "https://api.open-meteo.com/v1/forecast?latitude={}&longitude={}&current=temperature_2m,apparent_temperature,relative_humidity_2m,weather_code&hourly=temperature_2m,precipitation_probability&forecast_days=1&timeformat=unixtime&timezone=UTC",
-            coords.latitude, coords.longitude
-        );
-
-        let response: OpenMeteoResponse = self
-            .http
-            .get(url)
-            .send()
-            .await
-            .context("failed to call weather API")?
-            .error_for_status()
-            .context("weather API returned an error")?
-            .json()
-            .await
-            .context("failed to decode weather API response")?;
-
-        let observation_time = response
-            .current
-            .time
-            .and_then(|ts| Utc.timestamp_opt(ts, 0).latest())
-            .map(|dt| dt.with_timezone(&Local));
-
-        let snapshot = WeatherSnapshot {
-            temperature_c: response.current.temperature_2m,
-            feels_like_c: response.current.apparent_temperature,
-            humidity_pct: response.current.relative_humidity_2m,
-            weather_code: response.current.weather_code,
-            observation_time,
-        };
-
-        let forecast = self.collect_hourly_forecast(&response);
-
-        Ok(WeatherData { snapshot, forecast })
+    /// Whether an `If-None-Match` header names this image, so the client
+    /// already shows it.
+    fn matches(&self, if_none_match: &str) -> bool {
+        if_none_match.split(',').map(str::trim).any(|tag| {
+            // Weak comparison: a proxy may have marked the tag weak
+            tag == "*" || tag.strip_prefix("W/").unwrap_or(tag) == self.etag
+        })
     }
+}
 
-    fn collect_hourly_forecast(&self, response: &OpenMeteoResponse) -> Vec<HourlyForecast> {
-        let Some(hourly) = response.hourly.as_ref() else {
-            return Vec::new();
-        };
-
-        let now = Utc::now();
-        let mut last_included: Option<DateTime<Utc>> = None;
-        let mut periods = Vec::new();
-
-        for ((time, temp), precipitation) in hourly
-            .time
-            .iter()
-            .zip(hourly.temperature_2m.iter())
-            .zip(hourly.precipitation_probability.iter())
-        {
-            let timestamp = match Utc.timestamp_opt(*time, 0).latest() {
-                Some(value) => value,
-                None => continue,
-            };
-
-            if timestamp < now {
-                continue;
-            }
-
-            if let Some(last) = last_included {
-                if timestamp.signed_duration_since(last) < Duration::hours(2) {
-                    continue;
-                }
-            }
-
-            periods.push(HourlyForecast {
-                time: timestamp.with_timezone(&Local),
-                temperature_c: *temp,
-                precipitation_probability: *precipitation,
-            });
-            last_included = Some(timestamp);
-
-            if periods.len() >= 4 {
-                break;
-            }
-        }
-
-        periods
-    }
+struct SecondaryLocation {
+    name: String,
+    weather: Option<CachedWeather>,
 }
 
-#[derive(Deserialize)]
+#[derive(Default, Deserialize)]
 struct RenderParams {
     #[serde(rename = "batteryLevel")]
     battery_level: Option<u8>,
-    #[serde(rename = "isCharging", deserialize_with = "deserialize_bool_option")]
+    #[serde(
+        rename = "isCharging",
+        default,
+        deserialize_with = "deserialize_bool_option"
+    )]
     is_charging: Option<bool>,
+    device: Option<String>,
     width: Option<u32>,
     height: Option<u32>,
+    levels: Option<u16>,
+    dither: Option<Dither>,
+    rotate: Option<Rotation>,
+    /// Render now even if a pre-rendered image is available.
+    #[serde(default, deserialize_with = "deserialize_bool_option")]
+    refresh: Option<bool>,
+}
+
+impl RenderParams {
+    /// Whether the request changes the device profile's output, so the
+    /// profile's pre-rendered image doesn't fit.
+    fn overrides_device(&self) -> bool {
+        self.width.is_some()
+            || self.height.is_some()
+            || self.levels.is_some()
+            || self.dither.is_some()
+            || self.rotate.is_some()
+    }
 }
 
 #[derive(Deserialize)]
@@ -222,7 +246,7 @@ where
     })
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug)]
 struct Coordinates {
     latitude: f64,
     longitude: f64,
@@ -236,24 +260,117 @@ struct CoordinatesPath {
 
 #[derive(Debug, Clone)]
 struct WeatherSnapshot {
-    temperature_c: f64,
-    feels_like_c: f64,
+    temperature: f64,
+    feels_like: f64,
     humidity_pct: f64,
-    weather_code: i32,
-    observation_time: Option<DateTime<Local>>,
+    condition: WeatherCondition,
+    is_day: bool,
+    observation_time: Option<DateTime<Tz>>,
+    metrics: CurrentMetrics,
+}
+
+/// Secondary current conditions shown as chips; each provider fills in what
+/// it reports.
+#[derive(Debug, Clone, Default)]
+struct CurrentMetrics {
+    /// In the requested units (km/h or mph), like `wind_gusts`.
+    wind_speed: Option<f64>,
+    /// Direction the wind blows from, in degrees clockwise from north.
+    wind_direction: Option<f64>,
+    wind_gusts: Option<f64>,
+    /// Surface pressure in hPa.
+    pressure: Option<f64>,
+    /// Pressure change over the past three hours in hPa.
+    pressure_trend: Option<f64>,
+    uv_index: Option<f64>,
+    /// In metres.
+    visibility: Option<f64>,
+    /// In the requested temperature unit.
+    dew_point: Option<f64>,
+    cloud_cover_pct: Option<f64>,
 }
 
 #[derive(Debug, Clone)]
 struct WeatherData {
     snapshot: WeatherSnapshot,
-    forecast: Vec<HourlyForecast>,
+    /// Every hour the API returned, starting at local midnight; the widgets
+    /// pick the entries they show at render time.
+    hourly: Vec<HourlyForecast>,
+    /// 15-minute steps for the nowcast, from the current one onward. Only
+    /// Open-Meteo has these; other providers leave it empty.
+    minutely: Vec<MinutelyForecast>,
+    daily: Vec<DailyForecast>,
+    /// Timezone all timestamps are expressed in; the location's own zone
+    /// unless the config overrides it.
+    timezone: Tz,
+}
+
+impl WeatherData {
+    fn with_timezone(self, timezone: Tz) -> Self {
+        Self {
+            snapshot: WeatherSnapshot {
+                observation_time: self
+                    .snapshot
+                    .observation_time
+                    .map(|ts| ts.with_timezone(&timezone)),
+                ..self.snapshot
+            },
+            hourly: self
+                .hourly
+                .into_iter()
+                .map(|period| HourlyForecast {
+                    time: period.time.with_timezone(&timezone),
+                    ..period
+                })
+                .collect(),
+            minutely: self
+                .minutely
+                .into_iter()
+                .map(|step| MinutelyForecast {
+                    time: step.time.with_timezone(&timezone),
+                    ..step
+                })
+                .collect(),
+            daily: self
+                .daily
+                .into_iter()
+                .map(|day| DailyForecast {
+                    sunrise: day.sunrise.map(|ts| ts.with_timezone(&timezone)),
+                    sunset: day.sunset.map(|ts| ts.with_timezone(&timezone)),
+                    ..day
+                })
+                .collect(),
+            timezone,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 struct HourlyForecast {
-    time: DateTime<Local>,
-    temperature_c: f64,
-    precipitation_probability: f64,
+    time: DateTime<Tz>,
+    temperature: f64,
+    precipitation_probability: Option<f64>,
+}
+
+#[derive(Debug, Clone)]
+struct MinutelyForecast {
+    /// End of the 15 minute step.
+    time: DateTime<Tz>,
+    /// Total over the step, in the requested units.
+    precipitation: f64,
+}
+
+#[derive(Debug, Clone)]
+struct DailyForecast {
+    /// Calendar day at the location.
+    date: NaiveDate,
+    condition: WeatherCondition,
+    temperature_max: f64,
+    temperature_min: f64,
+    precipitation_sum: Option<f64>,
+    precipitation_probability: Option<f64>,
+    sunrise: Option<DateTime<Tz>>,
+    sunset: Option<DateTime<Tz>>,
 }
 
 #[derive(Template, WebTemplate)]
@@ -261,41 +378,66 @@ struct HourlyForecast {
 struct IndexTemplate {
     default_latitude: f64,
     default_longitude: f64,
+    device: String,
     width: u32,
     height: u32,
 }
 
-#[derive(Deserialize)]
-struct OpenMeteoResponse {
-    current: OpenMeteoCurrent,
-    hourly: Option<OpenMeteoHourly>,
+/// Disambiguation page for `/render/place/{name}`.
+#[derive(Template, WebTemplate)]
+#[template(path = "places.html")]
+struct PlacesTemplate {
+    query: String,
+    places: Vec<PlaceLink>,
 }
 
-#[derive(Deserialize)]
-struct OpenMeteoCurrent {
-    temperature_2m: f64,
-    apparent_temperature: f64,
-    relative_humidity_2m: f64,
-    weather_code: i32,
-    time: Option<i64>,
+struct PlaceLink {
+    label: String,
+    latitude: f64,
+    longitude: f64,
+    href: String,
 }
 
-#[derive(Deserialize)]
-struct OpenMeteoHourly {
-    time: Vec<i64>,
-    temperature_2m: Vec<f64>,
-    precipitation_probability: Vec<f64>,
+impl PlacesTemplate {
+    /// Links keep the request's query string, so device and battery
+    /// parameters survive the detour.
+    fn new(query: &str, places: &[Place], params: Option<&str>) -> Self {
+        let params = params
+            .filter(|params| !params.is_empty())
+            .map(|params| format!("?{params}"))
+            .unwrap_or_default();
+        Self {
+            query: query.to_string(),
+            places: places
+                .iter()
+                .map(|place| {
+                    let label = place.label();
+                    let path = utf8_percent_encode(&label, NON_ALPHANUMERIC);
+                    PlaceLink {
+                        href: format!("/render/place/{path}{params}"),
+                        label,
+                        latitude: place.latitude,
+                        longitude: place.longitude,
+                    }
+                })
+                .collect(),
+        }
+    }
 }
 
 #[derive(Default)]
 struct CliArgs {
     port: u16,
+    config: Option<PathBuf>,
 }
 
 impl CliArgs {
     fn parse() -> anyhow::Result<Self> {
         let mut args = env::args().skip(1);
-        let mut parsed = CliArgs { port: 4000 };
+        let mut parsed = CliArgs {
+            port: 4000,
+            config: None,
+        };
 
         while let Some(arg) = args.next() {
             match arg.as_str() {
@@ -307,6 +449,12 @@ impl CliArgs {
                         .parse()
                         .with_context(|| format!("invalid port value: {value}"))?;
                 }
+                "--config" | "-c" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| anyhow!("--config requires a value"))?;
+                    parsed.config = Some(PathBuf::from(value));
+                }
                 other => return Err(anyhow!("Unknown argument: {other}")),
             }
         }
@@ -327,15 +475,32 @@ async fn main() -> anyhow::Result<()> {
 
     let cli = CliArgs::parse()?;
 
+    let config_path = config::config_path(cli.config);
+    let config = DashboardConfig::load(&config_path)?;
+    log_config(&config_path, &config);
+
     let state = Arc::new(AppState {
-        client: WeatherClient::new(),
-        config: DashboardConfig::from_env(),
+        providers: WeatherProviders::new(),
+        cache: Arc::new(WeatherCache::new()),
+        config_path,
+        config: RwLock::new(Arc::new(config)),
         wanikani: WanikaniClient::new(),
+        air_quality: AirQualityClient::new(),
+        alerts: AlertSources::new(),
+        history: HistoryClient::new(),
+        geocoders: Geocoders::new(),
+        prerendered: Prerendered::new(),
     });
 
+    #[cfg(unix)]
+    tokio::spawn(reload_on_sighup(state.clone()));
+    tokio::spawn(prerender::run(state.clone()));
+
     let app = Router::new()
         .route("/", get(render_index))
         .route("/render/{latitude}/{longitude}", get(render_image))
+        .route("/render/place/{name}", get(render_place))
+        .route("/dashboard/{id}", get(render_location_set))
         .nest_service("/assets", ServeDir::new("assets"))
         .with_state(state);
 
@@ -352,6 +517,40 @@ async fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
+fn log_config(path: &std::path::Path, config: &DashboardConfig) {
+    info!(
+        path = %path.display(),
+        latitude = config.latitude,
+        longitude = config.longitude,
+        provider = ?config.provider,
+        device = %config.display.name,
+        devices = ?config.devices.keys().collect::<Vec<_>>(),
+        units = ?config.units,
+        locale = ?config.locale,
+        timezone = ?config.timezone,
+        icons = ?config.icons,
+        layout = ?config.layout,
+        metrics = ?config.metrics,
+        alerts = ?config.alerts.as_ref().map(|alerts| alerts.source),
+        dashboards = ?config.dashboards.keys().collect::<Vec<_>>(),
+        hourly = ?config.hourly,
+        prerender = config.prerender.len(),
+        "loaded dashboard config"
+    );
+}
+
+#[cfg(unix)]
+async fn reload_on_sighup(state: Arc<AppState>) {
+    let mut hangup = signal::unix::signal(signal::unix::SignalKind::hangup())
+        .expect("failed to install SIGHUP handler");
+
+    while hangup.recv().await.is_some() {
+        if let Err(err) = state.reload_config() {
+            error!(?err, "failed to reload config; keeping the previous one");
+        }
+    }
+}
+
 async fn shutdown_signal() {
     let ctrl_c = async {
         signal::ctrl_c()
@@ -379,11 +578,13 @@ async fn shutdown_signal() {
 }
 
 async fn render_index(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let config = state.config();
     IndexTemplate {
-        default_latitude: state.config.latitude,
-        default_longitude: state.config.longitude,
-        width: state.config.width,
-        height: state.config.height,
+        default_latitude: config.latitude,
+        default_longitude: config.longitude,
+        device: config.display.name.clone(),
+        width: config.display.width,
+        height: config.display.height,
     }
 }
 
@@ -391,224 +592,365 @@ async fn render_image(
     State(state): State<Arc<AppState>>,
     Path(path): Path<CoordinatesPath>,
     Query(params): Query<RenderParams>,
+    headers: HeaderMap,
 ) -> Result<Response, Response> {
-    let coords = state.config.coordinates(&path);
-    let dims = state.config.dimensions(&params);
-
-    let weather = match state.client.fetch_weather_data(coords).await {
-        Ok(data) => data,
-        Err(err) => {
-            error!(
-                ?err,
-                "failed to fetch weather; falling back to cached values"
-            );
-            WeatherData {
-                snapshot: WeatherSnapshot {
-                    temperature_c: 0.0,
-                    feels_like_c: 0.0,
-                    humidity_pct: 0.0,
-                    weather_code: 0,
-                    observation_time: None,
-                },
-                forecast: Vec::new(),
-            }
-        }
-    };
+    let config = state.config();
+    let target = RenderTarget::Location(config.coordinates(&path));
+    serve_target(&state, &config, &target, &params, &headers).await
+}
 
-    let day_label = weather
-        .snapshot
-        .observation_time
-        .as_ref()
-        .map(|ts| ts.format("%A").to_string())
-        .unwrap_or_else(|| "Today".to_string());
-
-    let kanji = match state.wanikani.fetch_pending_kanji(6).await {
-        Ok(list) => list,
-        Err(err) => {
-            error!(?err, "failed to fetch WaniKani data; showing placeholders");
-            Vec::new()
+/// Renders a place looked up by name or postal code. Names matching several
+/// places get a page listing them, each linking to a qualified name that
+/// only matches itself.
+async fn render_place(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+    RawQuery(query): RawQuery,
+    Query(params): Query<RenderParams>,
+    headers: HeaderMap,
+) -> Result<Response, Response> {
+    let config = state.config();
+    let places = state
+        .geocoders
+        .resolve(&config.geocoding, config.locale, &name)
+        .await
+        .map_err(bad_gateway)?;
+
+    match places.as_slice() {
+        [] => Err(not_found(anyhow!("no place matches `{name}`"))),
+        [place] => {
+            let device = config.device(&params).map_err(bad_request)?;
+            let image = render_dashboard(
+                &state,
+                &config,
+                &device,
+                place.coordinates(),
+                Some(&place.name),
+                &[],
+                &params,
+            )
+            .await
+            .map_err(internal_error_anyhow)?;
+            Ok(image_response(image, &headers))
         }
-    };
-
-    let inputs = build_dashboard_inputs(
-        (dims.0 / 2, dims.1 / 2),
-        &weather,
-        &day_label,
-        params.battery_level,
-        params.is_charging,
-        &kanji,
-    );
+        _ => Ok((
+            axum::http::StatusCode::MULTIPLE_CHOICES,
+            PlacesTemplate::new(&name, &places, query.as_deref()),
+        )
+            .into_response()),
+    }
+}
 
-    render_typst_document(inputs)
-}
-
-fn weather_description(code: &i32) -> &'static str {
-    match code {
-        0 => "Clear sky",
-        1 | 2 => "Mostly clear",
-        3 => "Overcast",
-        45 | 48 => "Fog",
-        51 | 53 | 55 => "Drizzle",
-        56 | 57 => "Freezing drizzle",
-        61 | 63 | 65 => "Rain",
-        66 | 67 => "Freezing rain",
-        71 | 73 | 75 => "Snowfall",
-        77 => "Snow grains",
-        80 | 81 | 82 => "Rain showers",
-        85 | 86 => "Snow showers",
-        95 => "Thunderstorm",
-        96 | 99 => "Thunderstorm with hail",
-        _ => "Unknown",
+async fn render_location_set(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Query(params): Query<RenderParams>,
+    headers: HeaderMap,
+) -> Result<Response, Response> {
+    let config = state.config();
+    if !config.dashboards.contains_key(&id) {
+        return Err(not_found(anyhow!("unknown dashboard `{id}`")));
     }
+    serve_target(
+        &state,
+        &config,
+        &RenderTarget::LocationSet(id),
+        &params,
+        &headers,
+    )
+    .await
 }
 
-fn build_dashboard_inputs(
-    dims: (u32, u32),
-    weather: &WeatherData,
-    day_label: &str,
-    battery_level: Option<u8>,
-    is_charging: Option<bool>,
-    kanji: &[WanikaniKanji],
-) -> Dict {
-    let condition = weather_description(&weather.snapshot.weather_code);
-    let temperature = format!("{:.0}°C", weather.snapshot.temperature_c.round());
-    let feels_like = format!("{:.0}°C", weather.snapshot.feels_like_c.round());
-    let humidity = format!("{:.0}%", weather.snapshot.humidity_pct.round());
-    let datetime_label = weather
-        .snapshot
-        .observation_time
-        .map(|ts| ts.format("%Y-%m-%d %H:%M").to_string())
-        .unwrap_or_else(|| "--".to_string());
-
-    let battery = match battery_level {
-        Some(level) if is_charging.unwrap_or(false) => {
-            format!("Battery {level}% (charging)")
+/// Answers from the pre-rendered image when `[[prerender]]` covers the
+/// target and device, unless the request overrides the device's settings or
+/// asks for `refresh`. Otherwise renders now, and keeps the result if it is
+/// one the scheduler maintains.
+async fn serve_target(
+    state: &AppState,
+    config: &DashboardConfig,
+    target: &RenderTarget,
+    params: &RenderParams,
+    headers: &HeaderMap,
+) -> Result<Response, Response> {
+    let device = config.device(params).map_err(bad_request)?;
+
+    let key = PrerenderKey::new(target, &device.name);
+    let prerendered = !params.overrides_device()
+        && config
+            .prerender
+            .iter()
+            .any(|settings| settings.key() == key);
+    if prerendered {
+        let battery = Battery {
+            level: params.battery_level,
+            charging: params.is_charging,
+        };
+        let image = state.prerendered.get(&key, battery);
+        if let Some(image) = image
+            && !params.refresh.unwrap_or(false)
+        {
+            return Ok(image_response(image, headers));
         }
-        Some(level) => format!("Battery {level}%"),
-        None => "Battery status unavailable".to_string(),
-    };
+    }
+
+    let image = render_target(state, config, target, &device, params)
+        .await
+        .map_err(internal_error_anyhow)?;
+    if prerendered {
+        state.prerendered.store(&key, image.clone());
+    }
+    Ok(image_response(image, headers))
+}
 
-    let updated = weather
-        .snapshot
-        .observation_time
-        .map(|ts| format!("Updated {}", ts.format("%Y-%m-%d %H:%M")))
-        .unwrap_or_else(|| "Updated --".to_string());
+/// Renders a coordinate or location set dashboard.
+async fn render_target(
+    state: &AppState,
+    config: &DashboardConfig,
+    target: &RenderTarget,
+    device: &DeviceProfile,
+    params: &RenderParams,
+) -> anyhow::Result<RenderedImage> {
+    match target {
+        RenderTarget::Location(coords) => {
+            render_dashboard(state, config, device, *coords, None, &[], params).await
+        }
+        RenderTarget::LocationSet(id) => {
+            let set = config
+                .dashboards
+                .get(id)
+                .with_context(|| format!("unknown dashboard `{id}`"))?;
+            render_dashboard(
+                state,
+                config,
+                device,
+                set.primary.coordinates(),
+                Some(&set.primary.name),
+                &set.secondary,
+                params,
+            )
+            .await
+        }
+    }
+}
 
-    // Build hourly forecast array
-    let mut hourly_cards: Vec<Value> = weather
-        .forecast
+/// Fetches everything the dashboard's widgets need for `coords` and renders
+/// the image, labelled with `name` if the place has one and with compact
+/// cards for `secondary` locations.
+async fn render_dashboard(
+    state: &AppState,
+    config: &DashboardConfig,
+    device: &DeviceProfile,
+    coords: Coordinates,
+    name: Option<&str>,
+    secondary: &[NamedLocation],
+    params: &RenderParams,
+) -> anyhow::Result<RenderedImage> {
+    let slots = config.layout.slots(!secondary.is_empty());
+    let mut sources: Vec<&'static dyn DataSource> = slots
         .iter()
-        .take(4)
-        .map(|period| {
-            let mut card = Dict::new();
-            card.insert(
-                Str::from("time"),
-                period.time.format("%I:%M %p").to_string().into_value(),
-            );
-            card.insert(
-                Str::from("temperature"),
-                format!("{:.0}°C", period.temperature_c.round()).into_value(),
-            );
-            card.insert(
-                Str::from("rain"),
-                format!("{:.0}%", period.precipitation_probability.round()).into_value(),
-            );
-            card.into_value()
-        })
+        .flat_map(|slot| widget::get(slot.widget).sources(config))
         .collect();
+    if config.alerts.is_some() {
+        sources.push(&AlertsSource);
+    }
+    let context = FetchContext::new(state, config, coords, secondary);
+    let data = source::fetch_all(&context, &sources).await;
+
+    let timezone = config
+        .timezone
+        .or_else(|| data.weather.as_ref().map(|cached| cached.data.timezone))
+        .unwrap_or_else(|| nautical_timezone(coords.longitude));
+    let context = WidgetContext {
+        config,
+        coords,
+        name,
+        params,
+        timezone,
+        now: Utc::now(),
+    };
+    let inputs = build_dashboard_inputs(device, &context, &slots, &data);
+
+    let bytes = render_typst_document(inputs, device, Some(timezone))?;
+    let modified = data.weather.as_ref().map(|cached| cached.fetched_at);
+    Ok(RenderedImage::new(
+        bytes,
+        device.format.content_type(),
+        modified,
+    ))
+}
+
+/// The image, or `304 Not Modified` when the request's `If-None-Match`
+/// names it, so device scripts can skip redrawing an unchanged screen.
+fn image_response(image: RenderedImage, request: &HeaderMap) -> Response {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::CONTENT_TYPE,
+        header::HeaderValue::from_static(image.content_type),
+    );
+    // Clients may keep the image but must check back before reusing it
+    headers.insert(
+        header::CACHE_CONTROL,
+        header::HeaderValue::from_static("no-cache"),
+    );
+    if let Ok(etag) = header::HeaderValue::from_str(&image.etag) {
+        headers.insert(header::ETAG, etag);
+    }
+    if let Some(modified) = image.modified {
+        let modified = modified.format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+        if let Ok(modified) = header::HeaderValue::from_str(&modified) {
+            headers.insert(header::LAST_MODIFIED, modified);
+        }
+    }
 
-    // Pad to 4 cards if needed
-    while hourly_cards.len() < 4 {
-        let mut card = Dict::new();
-        card.insert(Str::from("time"), "--".into_value());
-        card.insert(Str::from("temperature"), "--".into_value());
-        card.insert(Str::from("rain"), "--".into_value());
-        hourly_cards.push(card.into_value());
+    let unchanged = request
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| image.matches(value));
+    if unchanged {
+        return (axum::http::StatusCode::NOT_MODIFIED, headers).into_response();
     }
 
-    // Build wanikani entries array
-    let mut entries: Vec<Value> = kanji
+    (headers, image.bytes).into_response()
+}
+
+/// Banners for the most severe active alerts, with the expiry in the
+/// dashboard's timezone.
+fn build_alerts_data(alerts: &[Alert], timezone: Tz, locale: Locale) -> Dict {
+    let banners: Vec<Value> = alerts
         .iter()
-        .take(6)
-        .map(|item| {
-            let mut entry = Dict::new();
-            entry.insert(Str::from("kanji"), item.character.clone().into_value());
-            entry.insert(Str::from("meaning"), item.meaning.clone().into_value());
-            entry.into_value()
+        .take(MAX_ALERT_BANNERS)
+        .map(|alert| {
+            let mut banner = Dict::new();
+            banner.insert(Str::from("event"), alert.event.as_str().into_value());
+            banner.insert(Str::from("headline"), alert.headline.as_str().into_value());
+            banner.insert(Str::from("severity"), alert.severity.key().into_value());
+            if let Some(expires) = alert.expires {
+                banner.insert(
+                    Str::from("expires"),
+                    locale
+                        .format_until(&expires.with_timezone(&timezone))
+                        .into_value(),
+                );
+            }
+            banner.into_value()
         })
         .collect();
 
-    // Pad to 6 entries if needed
-    while entries.len() < 6 {
-        let mut entry = Dict::new();
-        entry.insert(Str::from("kanji"), "--".into_value());
-        entry.insert(Str::from("meaning"), "(no pending reviews)".into_value());
-        entries.push(entry.into_value());
-    }
+    let mut data = Dict::new();
+    data.insert(Str::from("alerts"), Array::from_iter(banners).into_value());
+    data
+}
 
-    // Build weather-data dict
-    let mut weather_data = Dict::new();
-    weather_data.insert(Str::from("day"), day_label.into_value());
-    weather_data.insert(Str::from("datetime"), datetime_label.into_value());
-    weather_data.insert(Str::from("condition"), condition.into_value());
-    weather_data.insert(Str::from("temperature"), temperature.into_value());
-    weather_data.insert(Str::from("real_feel"), feels_like.into_value());
-    weather_data.insert(Str::from("humidity"), humidity.into_value());
-    weather_data.insert(Str::from("battery"), battery.into_value());
-    weather_data.insert(Str::from("updated"), updated.into_value());
-    weather_data.insert(
-        Str::from("hours"),
-        Array::from_iter(hourly_cards).into_value(),
-    );
+fn build_dashboard_inputs(
+    device: &DeviceProfile,
+    context: &WidgetContext<'_>,
+    slots: &[WidgetSlot],
+    data: &DashboardData,
+) -> Dict {
+    let config = context.config;
 
-    // Build wanikani-data dict
-    let mut wanikani_data = Dict::new();
-    wanikani_data.insert(Str::from("entries"), Array::from_iter(entries).into_value());
+    // Each widget names its template module, whose `render-<key>` function
+    // draws it from `data`
+    let widgets = slots.iter().map(|slot| {
+        let widget = widget::get(slot.widget);
+        let mut entry = Dict::new();
+        entry.insert(Str::from("key"), slot.widget.key().into_value());
+        entry.insert(Str::from("template"), widget.template().into_value());
+        entry.insert(Str::from("span"), (slot.span as i64).into_value());
+        entry.insert(Str::from("data"), widget.build(context, data).into_value());
+        if widget.is_unavailable(config, data) {
+            entry.insert(
+                Str::from("unavailable"),
+                config.locale.text(Text::DataUnavailable).into_value(),
+            );
+        }
+        entry.into_value()
+    });
+    let mut layout = Dict::new();
+    layout.insert(
+        Str::from("columns"),
+        (config.layout.columns as i64).into_value(),
+    );
+    layout.insert(Str::from("widgets"), Array::from_iter(widgets).into_value());
+
+    // Build alerts-data dict
+    let alerts_data = build_alerts_data(&data.alerts, context.timezone, config.locale);
+
+    // Build device dict so templates can adapt to the panel
+    let mut device_data = Dict::new();
+    device_data.insert(Str::from("name"), device.name.as_str().into_value());
+    device_data.insert(Str::from("ppi"), (device.ppi as i64).into_value());
+    device_data.insert(
+        Str::from("gray-levels"),
+        (device.gray_levels as i64).into_value(),
+    );
+    device_data.insert(
+        Str::from("rotation"),
+        (device.rotation.degrees() as i64).into_value(),
+    );
 
-    // Build root inputs dict
+    // Build root inputs dict; the page is sized in points so the layout keeps
+    // the same physical size regardless of the panel's PPI
+    let pixel_per_pt = device.pixel_per_pt() as f64;
+    let (width, height) = device.logical_size();
     let mut inputs = Dict::new();
-    inputs.insert(Str::from("width"), (dims.0 as i64).into_value());
-    inputs.insert(Str::from("height"), (dims.1 as i64).into_value());
-    inputs.insert(Str::from("weather-data"), weather_data.into_value());
-    inputs.insert(Str::from("wanikani-data"), wanikani_data.into_value());
+    inputs.insert(
+        Str::from("width"),
+        (width as f64 / pixel_per_pt).into_value(),
+    );
+    inputs.insert(
+        Str::from("height"),
+        (height as f64 / pixel_per_pt).into_value(),
+    );
+    inputs.insert(Str::from("device"), device_data.into_value());
+    inputs.insert(Str::from("icon-theme"), config.icons.key().into_value());
+    inputs.insert(Str::from("layout"), layout.into_value());
+    inputs.insert(Str::from("alerts-data"), alerts_data.into_value());
 
     inputs
 }
 
-fn render_typst_document(inputs: Dict) -> Result<Response, Response> {
-    let typst_source = get_template(DASHBOARD_TEMPLATE).ok_or_else(|| {
-        internal_error_anyhow(anyhow!("template not found: {DASHBOARD_TEMPLATE}"))
-    })?;
-    let rgba = render_widget(&typst_source, DASHBOARD_TEMPLATE, 2.0, inputs)
-        .map_err(internal_error_anyhow)?;
-    let grayscale: ImageBuffer<Luma<u8>, Vec<u8>> =
-        DynamicImage::ImageRgba8(rgba).into_luma8().into();
+fn render_typst_document(
+    inputs: Dict,
+    device: &DeviceProfile,
+    timezone: Option<Tz>,
+) -> anyhow::Result<Vec<u8>> {
+    let typst_source = get_template(DASHBOARD_TEMPLATE)
+        .ok_or_else(|| anyhow!("template not found: {DASHBOARD_TEMPLATE}"))?;
+    let rgba = render_widget(
+        &typst_source,
+        DASHBOARD_TEMPLATE,
+        device.pixel_per_pt(),
+        inputs,
+        timezone,
+    )?;
+    let grayscale: ImageBuffer<Luma<u8>, Vec<u8>> = DynamicImage::ImageRgba8(rgba).into_luma8();
+    let mut grayscale = device.rotation.apply(grayscale);
+    quantize(&mut grayscale, device.gray_levels, device.dither);
+
+    if device.format == OutputFormat::Png {
+        return encode_png(&grayscale, device.gray_levels);
+    }
 
     let mut bytes: Vec<u8> = Vec::new();
     DynamicImage::ImageLuma8(grayscale)
-        .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
-        .map_err(internal_error)?;
+        .write_to(&mut Cursor::new(&mut bytes), device.format.image_format())
+        .context("failed to encode dashboard image")?;
 
-    let mut headers = HeaderMap::new();
-    headers.insert(
-        header::CONTENT_TYPE,
-        header::HeaderValue::from_static("image/png"),
-    );
-    headers.insert(
-        header::CACHE_CONTROL,
-        header::HeaderValue::from_static("no-store, max-age=0"),
-    );
+    Ok(bytes)
+}
 
-    Ok((headers, bytes).into_response())
+fn bad_request(err: anyhow::Error) -> Response {
+    (axum::http::StatusCode::BAD_REQUEST, format!("{err:#}")).into_response()
 }
 
-fn internal_error<E: std::error::Error>(err: E) -> Response {
-    error!(?err, "internal error while processing image");
-    (
-        axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-        "Internal Server Error",
-    )
-        .into_response()
+fn not_found(err: anyhow::Error) -> Response {
+    (axum::http::StatusCode::NOT_FOUND, format!("{err:#}")).into_response()
+}
+
+fn bad_gateway(err: anyhow::Error) -> Response {
+    error!(?err, "upstream service failed");
+    (axum::http::StatusCode::BAD_GATEWAY, "Bad Gateway").into_response()
 }
 
 fn internal_error_anyhow(err: anyhow::Error) -> Response {
@@ -619,3 +961,36 @@ fn internal_error_anyhow(err: anyhow::Error) -> Response {
     )
         .into_response()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn etag_is_the_sha256_of_the_image() {
+        let image = RenderedImage::new(b"abc".to_vec(), "image/png", None);
+        let etag = "\"ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad\"";
+        assert_eq!(image.etag, etag);
+
+        assert!(image.matches(etag));
+        assert!(image.matches(&format!("\"other\", W/{etag}")));
+        assert!(!image.matches("\"ba7816bf8f01cfea\""));
+    }
+
+    #[test]
+    fn last_modified_is_the_weather_fetch_time() {
+        let fetched_at = "2026-10-17T06:00:00Z".parse().unwrap();
+        let image = RenderedImage::new(b"abc".to_vec(), "image/png", Some(fetched_at));
+        let response = image_response(image, &HeaderMap::new());
+        assert_eq!(
+            response.headers()[header::LAST_MODIFIED],
+            "Sat, 17 Oct 2026 06:00:00 GMT"
+        );
+
+        // Without weather there is nothing to date the image by
+        let image = RenderedImage::new(b"abc".to_vec(), "image/png", None);
+        let response = image_response(image, &HeaderMap::new());
+        assert!(!response.headers().contains_key(header::LAST_MODIFIED));
+        assert!(response.headers().contains_key(header::ETAG));
+    }
+}
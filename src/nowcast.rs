@@ -0,0 +1,215 @@
+//! "Rain starting in ~25 min" summaries from 15-minute precipitation steps.
+
+use chrono::{DateTime, Duration, Utc};
+use chrono_tz::Tz;
+
+use crate::MinutelyForecast;
+use crate::units::Units;
+
+/// How far ahead the nowcast looks.
+pub const WINDOW_HOURS: i64 = 2;
+const STEP_MINUTES: i64 = 15;
+/// A step with less than this (mm in 15 minutes) counts as dry.
+const WET_MM: f64 = 0.1;
+/// Steps at or above this (mm in 15 minutes, ~10 mm/h) fill a whole bar.
+const HEAVY_MM: f64 = 2.5;
+/// Start times are rounded to this many minutes; finer looks more precise
+/// than the model is.
+const ROUND_MINUTES: i64 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Outlook {
+    Dry,
+    /// Rain begins within the window, `minutes` from now.
+    Starting {
+        minutes: i64,
+        stops_at: Option<DateTime<Tz>>,
+    },
+    /// Already raining. `stops_at` is `None` when it lasts the whole window.
+    Ongoing {
+        stops_at: Option<DateTime<Tz>>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct Nowcast {
+    pub outlook: Outlook,
+    /// Intensity of each step in the window from 0 to 1, for the bar strip.
+    pub bars: Vec<f64>,
+}
+
+impl Nowcast {
+    /// Summarizes the steps ending after `now` and starting within the
+    /// window. Each step holds the total for the 15 minutes before its
+    /// timestamp, in `units`. Returns `None` without at least half a window
+    /// of data, e.g. for providers that have no 15-minute forecast.
+    pub fn from_steps(
+        steps: &[MinutelyForecast],
+        now: DateTime<Utc>,
+        units: Units,
+    ) -> Option<Self> {
+        let step = Duration::minutes(STEP_MINUTES);
+        let end = now + Duration::hours(WINDOW_HOURS);
+        let window: Vec<&MinutelyForecast> = steps
+            .iter()
+            .filter(|entry| entry.time > now && entry.time - step < end)
+            .collect();
+        if (window.len() as i64) < WINDOW_HOURS * 60 / STEP_MINUTES / 2 {
+            return None;
+        }
+
+        let wet_threshold = units.precipitation_from_mm(WET_MM);
+        let heavy = units.precipitation_from_mm(HEAVY_MM);
+        let is_wet = |entry: &&MinutelyForecast| entry.precipitation >= wet_threshold;
+
+        let outlook = match window.iter().position(is_wet) {
+            None => Outlook::Dry,
+            Some(first_wet) => {
+                let stops_at = window[first_wet..]
+                    .iter()
+                    .find(|entry| !is_wet(entry))
+                    .map(|entry| entry.time - step);
+                let starts_at = window[first_wet].time - step;
+                if starts_at <= now {
+                    Outlook::Ongoing { stops_at }
+                } else {
+                    let minutes = starts_at.signed_duration_since(now).num_minutes();
+                    let rounded = (minutes + ROUND_MINUTES / 2) / ROUND_MINUTES * ROUND_MINUTES;
+                    Outlook::Starting {
+                        minutes: rounded.max(ROUND_MINUTES),
+                        stops_at,
+                    }
+                }
+            }
+        };
+
+        // Square root so drizzle still shows next to a downpour
+        let bars = window
+            .iter()
+            .map(|entry| (entry.precipitation.max(0.0) / heavy).sqrt().min(1.0))
+            .collect();
+
+        Some(Self { outlook, bars })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono_tz::Europe::Berlin;
+
+    use super::*;
+
+    /// 12:00 in Berlin.
+    fn start() -> DateTime<Utc> {
+        "2026-10-17T10:00:00Z".parse().unwrap()
+    }
+
+    fn at(minutes: i64) -> DateTime<Utc> {
+        start() + Duration::minutes(minutes)
+    }
+
+    /// Consecutive 15-minute steps from [`start`], each stamped with its end.
+    fn steps(amounts: &[f64]) -> Vec<MinutelyForecast> {
+        amounts
+            .iter()
+            .enumerate()
+            .map(|(i, &precipitation)| MinutelyForecast {
+                time: at((i as i64 + 1) * STEP_MINUTES).with_timezone(&Berlin),
+                precipitation,
+            })
+            .collect()
+    }
+
+    fn outlook(amounts: &[f64], now: DateTime<Utc>) -> Outlook {
+        Nowcast::from_steps(&steps(amounts), now, Units::Metric)
+            .unwrap()
+            .outlook
+    }
+
+    #[test]
+    fn dry_window() {
+        let nowcast = Nowcast::from_steps(&steps(&[0.0; 12]), at(7), Units::Metric).unwrap();
+        assert_eq!(nowcast.outlook, Outlook::Dry);
+        // 12:15 to 14:15, the last step overlapping the window's end
+        assert_eq!(nowcast.bars, [0.0; 9]);
+
+        // below the wet threshold
+        assert_eq!(outlook(&[0.05; 12], at(7)), Outlook::Dry);
+    }
+
+    #[test]
+    fn rain_starting_mid_window() {
+        let amounts = [0.0, 0.0, 0.0, 0.4, 2.5, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0];
+        let nowcast = Nowcast::from_steps(&steps(&amounts), at(7), Units::Metric).unwrap();
+
+        // starts at 12:45, 38 minutes from 12:07
+        assert_eq!(
+            nowcast.outlook,
+            Outlook::Starting {
+                minutes: 40,
+                stops_at: Some(at(75).with_timezone(&Berlin)),
+            }
+        );
+        // square root of the share of a heavy step
+        let expected = [0.0, 0.0, 0.0, 0.4, 1.0, 0.0];
+        for (bar, expected) in nowcast.bars.iter().zip(expected) {
+            assert!((bar - expected).abs() < 1e-9, "{:?}", nowcast.bars);
+        }
+    }
+
+    #[test]
+    fn rain_starting_in_a_minute_reports_five() {
+        let amounts = [0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0];
+        assert_eq!(
+            outlook(&amounts, at(29)),
+            Outlook::Starting {
+                minutes: 5,
+                stops_at: None,
+            }
+        );
+    }
+
+    #[test]
+    fn rain_already_ongoing() {
+        let amounts = [0.8, 0.8, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0];
+        assert_eq!(
+            outlook(&amounts, at(7)),
+            Outlook::Ongoing {
+                stops_at: Some(at(30).with_timezone(&Berlin)),
+            }
+        );
+
+        assert_eq!(
+            outlook(&[0.8; 12], at(7)),
+            Outlook::Ongoing { stops_at: None }
+        );
+    }
+
+    #[test]
+    fn needs_half_a_window_of_steps() {
+        assert!(Nowcast::from_steps(&steps(&[0.0; 3]), at(7), Units::Metric).is_none());
+        assert!(Nowcast::from_steps(&steps(&[0.0; 4]), at(7), Units::Metric).is_some());
+        // steps that already ended don't count
+        assert!(Nowcast::from_steps(&steps(&[0.0; 12]), at(135), Units::Metric).is_none());
+        assert!(Nowcast::from_steps(&[], at(7), Units::Metric).is_none());
+    }
+
+    #[test]
+    fn imperial_thresholds() {
+        // inches: 0.003 is under 0.1 mm, 0.01 is over
+        let amounts = [0.003, 0.003, 0.01, 0.0984, 0.0, 0.0, 0.0, 0.0, 0.0];
+        let nowcast = Nowcast::from_steps(&steps(&amounts), at(7), Units::Imperial).unwrap();
+        assert_eq!(
+            nowcast.outlook,
+            Outlook::Starting {
+                minutes: 25,
+                stops_at: Some(at(60).with_timezone(&Berlin)),
+            }
+        );
+        assert!((nowcast.bars[3] - 1.0).abs() < 0.01);
+
+        // the same numbers read as millimetres never reach the threshold
+        let metric = Nowcast::from_steps(&steps(&amounts), at(7), Units::Metric).unwrap();
+        assert_eq!(metric.outlook, Outlook::Dry);
+    }
+}
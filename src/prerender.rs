@@ -0,0 +1,196 @@
+//! Dashboards kept rendered in the background (`[[prerender]]`), so a polling
+//! device gets the latest image at once instead of waiting for the upstream
+//! APIs and a Typst compile.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::time::{Instant, sleep_until};
+use tracing::{error, info};
+
+use crate::config::DashboardConfig;
+use crate::{AppState, RenderParams, RenderTarget, RenderedImage, render_target};
+
+/// How often the scheduler wakes up anyway, to pick up a reloaded config.
+const CONFIG_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// A `[[prerender]]` entry.
+#[derive(Clone, Debug)]
+pub struct PrerenderSettings {
+    pub target: RenderTarget,
+    /// Device profile name; the `[display]` one unless set.
+    pub device: String,
+    pub interval: Duration,
+}
+
+impl PrerenderSettings {
+    pub fn key(&self) -> PrerenderKey {
+        PrerenderKey::new(&self.target, &self.device)
+    }
+}
+
+/// Target and device profile; locations are rounded to four decimals so
+/// the coordinates in a device's URL needn't match the config digit for digit.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PrerenderKey {
+    target: KeyTarget,
+    device: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum KeyTarget {
+    Location { latitude: i64, longitude: i64 },
+    LocationSet(String),
+}
+
+impl PrerenderKey {
+    pub fn new(target: &RenderTarget, device: &str) -> Self {
+        let target = match target {
+            RenderTarget::Location(coords) => KeyTarget::Location {
+                latitude: (coords.latitude * 10_000.0).round() as i64,
+                longitude: (coords.longitude * 10_000.0).round() as i64,
+            },
+            RenderTarget::LocationSet(id) => KeyTarget::LocationSet(id.clone()),
+        };
+        Self {
+            target,
+            device: device.to_string(),
+        }
+    }
+}
+
+/// Battery status from a device's latest request. Background renders have
+/// no request of their own, so they show this one.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Battery {
+    pub level: Option<u8>,
+    pub charging: Option<bool>,
+}
+
+#[derive(Default)]
+struct Entry {
+    image: Option<RenderedImage>,
+    battery: Battery,
+    /// Last render attempt, successful or not.
+    attempted: Option<Instant>,
+}
+
+/// Latest image per configured dashboard and device.
+pub struct Prerendered {
+    entries: Mutex<HashMap<PrerenderKey, Entry>>,
+}
+
+impl Prerendered {
+    pub fn new() -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The stored image for `key`, remembering `battery` for the next
+    /// background render.
+    pub fn get(&self, key: &PrerenderKey, battery: Battery) -> Option<RenderedImage> {
+        let mut entries = self.entries.lock().unwrap();
+        let entry = entries.entry(key.clone()).or_default();
+        entry.battery = battery;
+        entry.image.clone()
+    }
+
+    /// Replaces the stored image, e.g. after a forced synchronous render.
+    pub fn store(&self, key: &PrerenderKey, image: RenderedImage) {
+        let mut entries = self.entries.lock().unwrap();
+        let entry = entries.entry(key.clone()).or_default();
+        entry.image = Some(image);
+        entry.attempted = Some(Instant::now());
+    }
+
+    fn battery(&self, key: &PrerenderKey) -> Battery {
+        self.entries
+            .lock()
+            .unwrap()
+            .get(key)
+            .map(|entry| entry.battery)
+            .unwrap_or_default()
+    }
+
+    /// When `settings` is next due; now if it was never rendered.
+    fn due(&self, settings: &PrerenderSettings) -> Instant {
+        self.entries
+            .lock()
+            .unwrap()
+            .get(&settings.key())
+            .and_then(|entry| entry.attempted)
+            .map_or_else(Instant::now, |attempted| attempted + settings.interval)
+    }
+
+    /// Records a failed attempt, keeping the previous image.
+    fn failed(&self, key: &PrerenderKey) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.entry(key.clone()).or_default().attempted = Some(Instant::now());
+    }
+
+    /// Drops entries no longer in the config.
+    fn retain(&self, config: &DashboardConfig) {
+        let keys: Vec<PrerenderKey> = config.prerender.iter().map(|s| s.key()).collect();
+        self.entries
+            .lock()
+            .unwrap()
+            .retain(|key, _| keys.contains(key));
+    }
+}
+
+/// Re-renders each `[[prerender]]` entry whenever its interval has passed,
+/// one at a time so renders don't compete for the CPU.
+pub async fn run(state: Arc<AppState>) {
+    loop {
+        let config = state.config();
+        state.prerendered.retain(&config);
+
+        let mut wake = Instant::now() + CONFIG_CHECK_INTERVAL;
+        for settings in &config.prerender {
+            if state.prerendered.due(settings) <= Instant::now() {
+                refresh(&state, &config, settings).await;
+            }
+            wake = wake.min(state.prerendered.due(settings));
+        }
+        sleep_until(wake).await;
+    }
+}
+
+async fn refresh(state: &AppState, config: &DashboardConfig, settings: &PrerenderSettings) {
+    let key = settings.key();
+    let battery = state.prerendered.battery(&key);
+    let params = RenderParams {
+        battery_level: battery.level,
+        is_charging: battery.charging,
+        device: Some(settings.device.clone()),
+        ..RenderParams::default()
+    };
+
+    let started = Instant::now();
+    let rendered = async {
+        let device = config.device(&params)?;
+        render_target(state, config, &settings.target, &device, &params).await
+    };
+    match rendered.await {
+        Ok(image) => {
+            info!(
+                dashboard = ?settings.target,
+                device = %settings.device,
+                elapsed_ms = started.elapsed().as_millis() as u64,
+                "pre-rendered dashboard"
+            );
+            state.prerendered.store(&key, image);
+        }
+        Err(err) => {
+            error!(
+                ?err,
+                dashboard = ?settings.target,
+                device = %settings.device,
+                "failed to pre-render dashboard; keeping the previous image"
+            );
+            state.prerendered.failed(&key);
+        }
+    }
+}
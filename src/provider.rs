@@ -0,0 +1,461 @@
+use std::collections::HashMap;
+use std::env;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use futures::future::BoxFuture;
+use reqwest::Client;
+use serde::Deserialize;
+use tracing::warn;
+
+use crate::cache::RETRY_AFTER;
+use crate::condition::WeatherCondition;
+use crate::config::DashboardConfig;
+use crate::{Coordinates, DailyForecast, HourlyForecast, WeatherData, WeatherQuery};
+
+mod met_norway;
+mod nws;
+mod open_meteo;
+mod openweathermap;
+
+use met_norway::MetNorway;
+use nws::Nws;
+use open_meteo::OpenMeteo;
+use openweathermap::OpenWeatherMap;
+
+/// MET Norway and weather.gov reject requests without an identifying agent.
+pub(crate) const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+/// Weather backend a location is served from (`location.provider`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProviderKind {
+    #[default]
+    OpenMeteo,
+    MetNorway,
+    #[serde(rename = "openweathermap")]
+    OpenWeatherMap,
+    Nws,
+}
+
+pub trait WeatherProvider: Send + Sync {
+    fn kind(&self) -> ProviderKind;
+
+    /// Current conditions plus hourly and daily forecasts, with values in
+    /// `query.units` and timestamps in the location's timezone.
+    fn fetch(
+        &self,
+        coords: Coordinates,
+        query: WeatherQuery,
+    ) -> BoxFuture<'_, anyhow::Result<WeatherData>>;
+}
+
+/// Holds the HTTP client and endpoints shared by all backends. Base URLs can
+/// be pointed elsewhere through the environment, e.g. at a local mock.
+#[derive(Clone)]
+pub struct WeatherProviders {
+    http: Client,
+    open_meteo_base_url: String,
+    met_norway_base_url: String,
+    openweathermap_base_url: String,
+    nws_base_url: String,
+    timezones: Arc<TimezoneLookup>,
+}
+
+impl WeatherProviders {
+    pub fn new() -> Self {
+        let http = Client::builder()
+            .user_agent(USER_AGENT)
+            .build()
+            .expect("failed to build HTTP client");
+
+        let open_meteo_base_url = base_url("WEATHER_API_BASE_URL", "https://api.open-meteo.com");
+        let timezones = Arc::new(TimezoneLookup::new(
+            http.clone(),
+            open_meteo_base_url.clone(),
+        ));
+
+        Self {
+            http,
+            open_meteo_base_url,
+            met_norway_base_url: base_url("MET_NORWAY_BASE_URL", "https://api.met.no"),
+            openweathermap_base_url: base_url(
+                "OPENWEATHERMAP_BASE_URL",
+                "https://api.openweathermap.org",
+            ),
+            nws_base_url: base_url("NWS_BASE_URL", "https://api.weather.gov"),
+            timezones,
+        }
+    }
+
+    pub fn get(&self, kind: ProviderKind, config: &DashboardConfig) -> Box<dyn WeatherProvider> {
+        let http = self.http.clone();
+        match kind {
+            ProviderKind::OpenMeteo => Box::new(OpenMeteo {
+                http,
+                base_url: self.open_meteo_base_url.clone(),
+            }),
+            ProviderKind::MetNorway => Box::new(MetNorway {
+                http,
+                base_url: self.met_norway_base_url.clone(),
+                timezone: config.timezone,
+                timezones: self.timezones.clone(),
+            }),
+            ProviderKind::OpenWeatherMap => Box::new(OpenWeatherMap {
+                http,
+                base_url: self.openweathermap_base_url.clone(),
+                api_key: config.openweathermap_key.clone(),
+                timezone: config.timezone,
+                timezones: self.timezones.clone(),
+            }),
+            ProviderKind::Nws => Box::new(Nws {
+                http,
+                base_url: self.nws_base_url.clone(),
+            }),
+        }
+    }
+
+    /// The location's zone without waiting for a weather backend: the
+    /// config's `timezone`, else a lookup, else the nautical zone.
+    pub async fn timezone(&self, configured: Option<Tz>, coords: Coordinates) -> Tz {
+        self.timezones
+            .resolve_configured(configured, coords)
+            .await
+            .unwrap_or_else(|err| lookup_fallback(err, nautical_timezone(coords.longitude)))
+    }
+}
+
+/// IANA zones looked up through Open-Meteo's `timezone=auto`, for backends
+/// whose responses carry no zone or only the current UTC offset. A location's
+/// zone doesn't change, so each one is only looked up once.
+pub struct TimezoneLookup {
+    http: Client,
+    base_url: String,
+    /// Keyed by coordinates rounded to two decimals, like the weather cache.
+    zones: Mutex<HashMap<(i32, i32), Tz>>,
+    /// When a location whose lookup failed or was given up on may be asked
+    /// for again, so a dead API isn't waited on by every render.
+    retry_at: Mutex<HashMap<(i32, i32), DateTime<Utc>>>,
+}
+
+impl TimezoneLookup {
+    fn new(http: Client, base_url: String) -> Self {
+        Self {
+            http,
+            base_url,
+            zones: Mutex::new(HashMap::new()),
+            retry_at: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn resolve(&self, coords: Coordinates) -> anyhow::Result<Tz> {
+        let key = (
+            (coords.latitude * 100.0).round() as i32,
+            (coords.longitude * 100.0).round() as i32,
+        );
+        if let Some(timezone) = self.zones.lock().unwrap().get(&key) {
+            return Ok(*timezone);
+        }
+        if let Some(retry_at) = self.retry_at.lock().unwrap().get(&key)
+            && Utc::now() < *retry_at
+        {
+            anyhow::bail!("timezone lookup failed recently; retrying after {retry_at}");
+        }
+
+        // Backs off unless disarmed, which also covers a caller's timeout
+        // dropping the request halfway
+        let mut backoff = Backoff {
+            retry_at: &self.retry_at,
+            key,
+            armed: true,
+        };
+
+        let url = format!(
+            "{}/v1/forecast?latitude={}&longitude={}&timezone=auto&forecast_days=1",
+            self.base_url, coords.latitude, coords.longitude
+        );
+        let response: ZoneResponse = self
+            .http
+            .get(url)
+            .send()
+            .await
+            .context("failed to call timezone lookup API")?
+            .error_for_status()
+            .context("timezone lookup API returned an error")?
+            .json()
+            .await
+            .context("failed to decode timezone lookup response")?;
+        let timezone: Tz = response
+            .timezone
+            .parse()
+            .map_err(|_| anyhow::anyhow!("unknown timezone `{}`", response.timezone))?;
+
+        backoff.armed = false;
+        self.retry_at.lock().unwrap().remove(&key);
+        self.zones.lock().unwrap().insert(key, timezone);
+        Ok(timezone)
+    }
+
+    /// The config's `timezone` if set, otherwise the looked up zone.
+    async fn resolve_configured(
+        &self,
+        configured: Option<Tz>,
+        coords: Coordinates,
+    ) -> anyhow::Result<Tz> {
+        match configured {
+            Some(timezone) => Ok(timezone),
+            None => self.resolve(coords).await,
+        }
+    }
+}
+
+/// Records a failed lookup when dropped while armed.
+struct Backoff<'a> {
+    retry_at: &'a Mutex<HashMap<(i32, i32), DateTime<Utc>>>,
+    key: (i32, i32),
+    armed: bool,
+}
+
+impl Drop for Backoff<'_> {
+    fn drop(&mut self) {
+        if self.armed {
+            let retry_at = Utc::now() + RETRY_AFTER;
+            self.retry_at.lock().unwrap().insert(self.key, retry_at);
+        }
+    }
+}
+
+/// Zone to use after a failed lookup. A fixed offset doesn't follow daylight
+/// saving time, so that gets a warning.
+fn lookup_fallback(err: anyhow::Error, timezone: Tz) -> Tz {
+    warn!(
+        ?err,
+        %timezone,
+        "failed to look up the location's timezone; local times ignore daylight saving time unless `timezone` is set"
+    );
+    timezone
+}
+
+#[derive(Deserialize)]
+struct ZoneResponse {
+    timezone: String,
+}
+
+/// Open-Meteo backend talking to `base_url`, for tests against a mock server.
+#[cfg(test)]
+pub(crate) fn open_meteo(base_url: &str) -> Box<dyn WeatherProvider> {
+    Box::new(OpenMeteo {
+        http: Client::new(),
+        base_url: base_url.to_string(),
+    })
+}
+
+pub(crate) fn base_url(var: &str, default: &str) -> String {
+    env::var(var)
+        .unwrap_or_else(|_| default.to_string())
+        .trim_end_matches('/')
+        .to_string()
+}
+
+/// Apparent temperature after Steadman (the formula Open-Meteo uses), for
+/// providers that don't report a feels-like value themselves.
+fn apparent_temperature(celsius: f64, humidity_pct: f64, wind_ms: f64) -> f64 {
+    let vapour_pressure =
+        humidity_pct / 100.0 * 6.105 * (17.27 * celsius / (237.7 + celsius)).exp();
+    celsius + 0.33 * vapour_pressure - 0.70 * wind_ms - 4.00
+}
+
+/// Zone for a fixed UTC offset, for providers that report an offset rather
+/// than a zone name and the zone lookup failed. Only whole hours have an
+/// `Etc/GMT` zone; anything else falls back to UTC.
+pub(crate) fn offset_timezone(offset_secs: i64) -> Tz {
+    if offset_secs % 3600 != 0 {
+        return Tz::UTC;
+    }
+    // POSIX sign convention: Etc/GMT-7 is seven hours ahead of UTC
+    format!("Etc/GMT{:+}", -offset_secs / 3600)
+        .parse()
+        .unwrap_or(Tz::UTC)
+}
+
+/// Whole-hour zone for a longitude (15° per hour), for when nothing better
+/// is known about the location.
+pub(crate) fn nautical_timezone(longitude: f64) -> Tz {
+    offset_timezone((longitude / 15.0).round() as i64 * 3600)
+}
+
+/// One forecast step from providers without a daily endpoint, in the
+/// location's timezone and the requested units.
+struct Sample {
+    time: DateTime<Tz>,
+    temperature: f64,
+    /// Amount over the step (1 h, 3 h or 6 h depending on the provider).
+    precipitation: Option<f64>,
+    precipitation_probability: Option<f64>,
+    condition: WeatherCondition,
+    is_day: bool,
+}
+
+impl Sample {
+    fn hourly(&self) -> HourlyForecast {
+        HourlyForecast {
+            time: self.time,
+            temperature: self.temperature,
+            precipitation_probability: self.precipitation_probability,
+        }
+    }
+}
+
+/// Folds forecast steps into calendar days. The first day only covers the
+/// hours still ahead, so its high and low can differ from a daily endpoint's.
+fn daily_from_samples(samples: &[Sample]) -> Vec<DailyForecast> {
+    let mut days: Vec<DailyForecast> = Vec::new();
+
+    for sample in samples {
+        let date = sample.time.date_naive();
+        match days.last_mut() {
+            Some(day) if day.date == date => {
+                day.temperature_max = day.temperature_max.max(sample.temperature);
+                day.temperature_min = day.temperature_min.min(sample.temperature);
+                day.precipitation_sum = match (day.precipitation_sum, sample.precipitation) {
+                    (Some(total), Some(amount)) => Some(total + amount),
+                    (total, amount) => total.or(amount),
+                };
+                day.precipitation_probability = match (
+                    day.precipitation_probability,
+                    sample.precipitation_probability,
+                ) {
+                    (Some(max), Some(probability)) => Some(max.max(probability)),
+                    (max, probability) => max.or(probability),
+                };
+                if sample.condition.severity() > day.condition.severity() {
+                    day.condition = sample.condition;
+                }
+            }
+            _ => days.push(DailyForecast {
+                date,
+                condition: sample.condition,
+                temperature_max: sample.temperature,
+                temperature_min: sample.temperature,
+                precipitation_sum: sample.precipitation,
+                precipitation_probability: sample.precipitation_probability,
+                sunrise: None,
+                sunset: None,
+            }),
+        }
+    }
+
+    days
+}
+
+/// Asserts two derived values match, ignoring floating point noise.
+#[cfg(test)]
+#[track_caller]
+pub(crate) fn assert_close(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() < 1e-6,
+        "expected {expected}, got {actual}"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use axum::Router;
+    use axum::extract::Query;
+    use axum::routing::get;
+
+    use super::*;
+
+    #[test]
+    fn offset_zones_use_posix_signs() {
+        assert_eq!(offset_timezone(7200).name(), "Etc/GMT-2");
+        assert_eq!(offset_timezone(-18000).name(), "Etc/GMT+5");
+        assert_eq!(offset_timezone(19800), Tz::UTC);
+        assert_eq!(nautical_timezone(10.75).name(), "Etc/GMT-1");
+    }
+
+    #[tokio::test]
+    async fn looks_up_each_zone_once() {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let handler = {
+            let requests = requests.clone();
+            move || async move {
+                requests.fetch_add(1, Ordering::SeqCst);
+                r#"{"latitude":59.9,"longitude":10.75,"utc_offset_seconds":7200,"timezone":"Europe/Oslo","timezone_abbreviation":"GMT+2"}"#
+            }
+        };
+        let app = Router::new().route("/v1/forecast", get(handler));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let lookup = TimezoneLookup::new(Client::new(), base_url);
+        let oslo = Coordinates {
+            latitude: 59.9139,
+            longitude: 10.7522,
+        };
+
+        assert_eq!(lookup.resolve(oslo).await.unwrap(), chrono_tz::Europe::Oslo);
+        assert_eq!(lookup.resolve(oslo).await.unwrap(), chrono_tz::Europe::Oslo);
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        let configured = lookup
+            .resolve_configured(Some(chrono_tz::Asia::Tokyo), oslo)
+            .await
+            .unwrap();
+        assert_eq!(configured, chrono_tz::Asia::Tokyo);
+    }
+
+    #[tokio::test]
+    async fn backs_off_after_a_failed_lookup() {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let handler = {
+            let requests = requests.clone();
+            move |Query(query): Query<HashMap<String, String>>| async move {
+                requests.fetch_add(1, Ordering::SeqCst);
+                // Oslo hangs, everything else fails outright
+                if query["latitude"].starts_with("59.") {
+                    tokio::time::sleep(std::time::Duration::from_secs(10)).await;
+                }
+                axum::http::StatusCode::SERVICE_UNAVAILABLE
+            }
+        };
+        let app = Router::new().route("/v1/forecast", get(handler));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let lookup = TimezoneLookup::new(Client::new(), base_url);
+        let berlin = Coordinates {
+            latitude: 52.52,
+            longitude: 13.41,
+        };
+        let oslo = Coordinates {
+            latitude: 59.91,
+            longitude: 10.75,
+        };
+
+        assert!(lookup.resolve(berlin).await.is_err());
+        let err = lookup.resolve(berlin).await.unwrap_err();
+        assert!(err.to_string().contains("failed recently"), "{err}");
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        // A caller giving up counts as a failure too
+        let wait = std::time::Duration::from_millis(100);
+        assert!(
+            tokio::time::timeout(wait, lookup.resolve(oslo))
+                .await
+                .is_err()
+        );
+        assert!(lookup.resolve(oslo).await.is_err());
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+        lookup.retry_at.lock().unwrap().clear();
+        assert!(lookup.resolve(berlin).await.is_err());
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+    }
+}
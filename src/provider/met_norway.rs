@@ -0,0 +1,368 @@
+use std::sync::Arc;
+
+use anyhow::{Context, bail};
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use futures::future::BoxFuture;
+use reqwest::Client;
+use serde::Deserialize;
+
+use super::{
+    ProviderKind, Sample, TimezoneLookup, WeatherProvider, apparent_temperature,
+    daily_from_samples, lookup_fallback, nautical_timezone,
+};
+use crate::condition::{Intensity, WeatherCondition};
+use crate::units::Units;
+use crate::{Coordinates, CurrentMetrics, WeatherData, WeatherQuery, WeatherSnapshot};
+
+/// MET Norway's Locationforecast 2.0. Hourly steps for the first ~2.5 days,
+/// then every 6 hours; all values are metric.
+pub struct MetNorway {
+    pub(super) http: Client,
+    pub(super) base_url: String,
+    /// The config's `timezone`; looked up when unset.
+    pub(super) timezone: Option<Tz>,
+    pub(super) timezones: Arc<TimezoneLookup>,
+}
+
+impl WeatherProvider for MetNorway {
+    fn kind(&self) -> ProviderKind {
+        ProviderKind::MetNorway
+    }
+
+    fn fetch(
+        &self,
+        coords: Coordinates,
+        query: WeatherQuery,
+    ) -> BoxFuture<'_, anyhow::Result<WeatherData>> {
+        Box::pin(self.fetch_weather_data(coords, query))
+    }
+}
+
+impl MetNorway {
+    async fn fetch_weather_data(
+        &self,
+        coords: Coordinates,
+        query: WeatherQuery,
+    ) -> anyhow::Result<WeatherData> {
+        // The API asks for at most four decimals so responses can be cached
+        let url = format!(
+            "{}/weatherapi/locationforecast/2.0/complete?lat={:.4}&lon={:.4}",
+            self.base_url, coords.latitude, coords.longitude,
+        );
+
+        let request = async {
+            self.http
+                .get(url)
+                .send()
+                .await
+                .context("failed to call MET Norway API")?
+                .error_for_status()
+                .context("MET Norway API returned an error")?
+                .json::<LocationForecast>()
+                .await
+                .context("failed to decode MET Norway response")
+        };
+        // The response carries no timezone, so it is looked up alongside;
+        // the nautical zone for the longitude is the last resort
+        let (response, timezone) = tokio::join!(
+            request,
+            self.timezones.resolve_configured(self.timezone, coords),
+        );
+        let response = response?;
+        let timezone = timezone
+            .unwrap_or_else(|err| lookup_fallback(err, nautical_timezone(coords.longitude)));
+
+        weather_data(&response, timezone, query.units)
+    }
+}
+
+/// Maps a Locationforecast response, with times in `timezone`.
+fn weather_data(
+    response: &LocationForecast,
+    timezone: Tz,
+    units: Units,
+) -> anyhow::Result<WeatherData> {
+    let samples: Vec<Sample> = response
+        .properties
+        .timeseries
+        .iter()
+        .map(|step| {
+            let period = step.data.period();
+            let details = period.and_then(|period| period.details.as_ref());
+            Sample {
+                time: step.time.with_timezone(&timezone),
+                temperature: units
+                    .temperature_from_celsius(step.data.instant.details.air_temperature),
+                precipitation: details
+                    .and_then(|details| details.precipitation_amount)
+                    .map(|mm| units.precipitation_from_mm(mm)),
+                precipitation_probability: details
+                    .and_then(|details| details.probability_of_precipitation),
+                condition: period.map_or(WeatherCondition::Unknown, |period| {
+                    condition_from_symbol(&period.summary.symbol_code)
+                }),
+                is_day: period.is_none_or(|period| !period.summary.symbol_code.ends_with("_night")),
+            }
+        })
+        .collect();
+
+    let Some(first) = response.properties.timeseries.first() else {
+        bail!("MET Norway response has no forecast steps");
+    };
+    let instant = &first.data.instant.details;
+    let humidity_pct = instant.relative_humidity.unwrap_or_default();
+    let snapshot = WeatherSnapshot {
+        temperature: units.temperature_from_celsius(instant.air_temperature),
+        feels_like: units.temperature_from_celsius(apparent_temperature(
+            instant.air_temperature,
+            humidity_pct,
+            instant.wind_speed.unwrap_or_default(),
+        )),
+        humidity_pct,
+        condition: samples[0].condition,
+        is_day: samples[0].is_day,
+        observation_time: Some(first.time.with_timezone(&timezone)),
+        metrics: CurrentMetrics {
+            wind_speed: instant.wind_speed.map(|ms| units.wind_speed_from_ms(ms)),
+            wind_direction: instant.wind_from_direction,
+            wind_gusts: instant
+                .wind_speed_of_gust
+                .map(|ms| units.wind_speed_from_ms(ms)),
+            // The only pressure reported is reduced to sea level
+            pressure: instant.air_pressure_at_sea_level,
+            pressure_trend: None,
+            uv_index: instant.ultraviolet_index_clear_sky,
+            visibility: None,
+            dew_point: instant
+                .dew_point_temperature
+                .map(|celsius| units.temperature_from_celsius(celsius)),
+            cloud_cover_pct: instant.cloud_area_fraction,
+        },
+    };
+
+    Ok(WeatherData {
+        snapshot,
+        hourly: samples.iter().map(Sample::hourly).collect(),
+        minutely: Vec::new(),
+        daily: daily_from_samples(&samples),
+        timezone,
+    })
+}
+
+/// Maps a MET Norway symbol code such as `lightrainshowers_day`. The day,
+/// night or polar twilight suffix is handled by the caller.
+fn condition_from_symbol(symbol: &str) -> WeatherCondition {
+    let name = symbol.split('_').next().unwrap_or(symbol);
+    let (intensity, precipitation) = if let Some(rest) = name.strip_prefix("light") {
+        (Intensity::Light, rest)
+    } else if let Some(rest) = name.strip_prefix("heavy") {
+        (Intensity::Heavy, rest)
+    } else {
+        (Intensity::Moderate, name)
+    };
+
+    match precipitation {
+        "clearsky" => WeatherCondition::Clear,
+        "fair" => WeatherCondition::MainlyClear,
+        "partlycloudy" => WeatherCondition::PartlyCloudy,
+        "cloudy" => WeatherCondition::Overcast,
+        "fog" => WeatherCondition::Fog,
+        name if name.contains("thunder") => WeatherCondition::Thunderstorm,
+        name if name.contains("sleet") => WeatherCondition::FreezingRain(intensity),
+        "snowshowers" => WeatherCondition::SnowShowers(intensity),
+        "snow" => WeatherCondition::Snowfall(intensity),
+        "rainshowers" => WeatherCondition::RainShowers(intensity),
+        "rain" => WeatherCondition::Rain(intensity),
+        _ => WeatherCondition::Unknown,
+    }
+}
+
+#[derive(Deserialize)]
+struct LocationForecast {
+    properties: Properties,
+}
+
+#[derive(Deserialize)]
+struct Properties {
+    timeseries: Vec<TimeStep>,
+}
+
+#[derive(Deserialize)]
+struct TimeStep {
+    time: DateTime<Utc>,
+    data: StepData,
+}
+
+#[derive(Deserialize)]
+struct StepData {
+    instant: Instant,
+    next_1_hours: Option<Period>,
+    next_6_hours: Option<Period>,
+}
+
+impl StepData {
+    /// Steps past the hourly range only carry the 6 hour summary.
+    fn period(&self) -> Option<&Period> {
+        self.next_1_hours.as_ref().or(self.next_6_hours.as_ref())
+    }
+}
+
+#[derive(Deserialize)]
+struct Instant {
+    details: InstantDetails,
+}
+
+#[derive(Deserialize)]
+struct InstantDetails {
+    air_temperature: f64,
+    relative_humidity: Option<f64>,
+    wind_speed: Option<f64>,
+    wind_from_direction: Option<f64>,
+    wind_speed_of_gust: Option<f64>,
+    air_pressure_at_sea_level: Option<f64>,
+    dew_point_temperature: Option<f64>,
+    cloud_area_fraction: Option<f64>,
+    /// Only in the `complete` product; ignores cloud cover.
+    ultraviolet_index_clear_sky: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct Period {
+    summary: Summary,
+    details: Option<PeriodDetails>,
+}
+
+#[derive(Deserialize)]
+struct Summary {
+    symbol_code: String,
+}
+
+#[derive(Deserialize)]
+struct PeriodDetails {
+    precipitation_amount: Option<f64>,
+    probability_of_precipitation: Option<f64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::provider::assert_close;
+
+    fn fixture() -> LocationForecast {
+        serde_json::from_str(include_str!("../../tests/fixtures/met_norway.json")).unwrap()
+    }
+
+    #[test]
+    fn maps_current_conditions() {
+        let data = weather_data(&fixture(), chrono_tz::Europe::Oslo, Units::Metric).unwrap();
+        let snapshot = &data.snapshot;
+
+        assert_close(snapshot.temperature, 7.2);
+        assert_close(snapshot.humidity_pct, 88.1);
+        assert_eq!(snapshot.condition, WeatherCondition::Overcast);
+        assert!(snapshot.is_day);
+        assert_eq!(
+            snapshot.observation_time.unwrap().to_rfc3339(),
+            "2026-10-24T20:00:00+02:00"
+        );
+        assert_close(snapshot.metrics.wind_speed.unwrap(), 3.4 * 3.6);
+        assert_close(snapshot.metrics.wind_gusts.unwrap(), 7.9 * 3.6);
+        assert_eq!(snapshot.metrics.pressure, Some(1003.8));
+        assert_eq!(snapshot.metrics.dew_point, Some(5.3));
+    }
+
+    #[test]
+    fn keeps_local_time_across_the_end_of_dst() {
+        let data = weather_data(&fixture(), chrono_tz::Europe::Oslo, Units::Metric).unwrap();
+        let local: Vec<String> = data
+            .hourly
+            .iter()
+            .map(|step| step.time.format("%d %H:%M %:z").to_string())
+            .collect();
+
+        // Hourly steps, then MET Norway's 6-hour steps; DST ends at 01:00 UTC
+        assert_eq!(
+            local,
+            [
+                "24 20:00 +02:00",
+                "24 21:00 +02:00",
+                "24 22:00 +02:00",
+                "24 23:00 +02:00",
+                "25 00:00 +02:00",
+                "25 02:00 +02:00",
+                "25 07:00 +01:00",
+                "25 13:00 +01:00",
+                "25 19:00 +01:00",
+                "26 01:00 +01:00",
+            ]
+        );
+    }
+
+    #[test]
+    fn aggregates_mixed_steps_into_days() {
+        let data = weather_data(&fixture(), chrono_tz::Europe::Oslo, Units::Metric).unwrap();
+        let dates: Vec<NaiveDate> = data.daily.iter().map(|day| day.date).collect();
+        assert_eq!(
+            dates,
+            [
+                NaiveDate::from_ymd_opt(2026, 10, 24).unwrap(),
+                NaiveDate::from_ymd_opt(2026, 10, 25).unwrap(),
+                NaiveDate::from_ymd_opt(2026, 10, 26).unwrap(),
+            ]
+        );
+
+        let evening = &data.daily[0];
+        assert_close(evening.temperature_max, 7.2);
+        assert_close(evening.temperature_min, 6.4);
+        assert_close(evening.precipitation_sum.unwrap(), 1.8);
+        assert_eq!(evening.precipitation_probability, Some(71.3));
+        assert_eq!(
+            evening.condition,
+            WeatherCondition::Rain(Intensity::Moderate)
+        );
+
+        // Sums the 1-hour amount of the last hourly step with the 6-hour ones
+        let sunday = &data.daily[1];
+        assert_close(sunday.temperature_max, 9.3);
+        assert_close(sunday.temperature_min, 4.9);
+        assert_close(sunday.precipitation_sum.unwrap(), 0.6);
+        assert_eq!(sunday.precipitation_probability, Some(35.0));
+        assert_eq!(
+            sunday.condition,
+            WeatherCondition::RainShowers(Intensity::Light)
+        );
+        assert_eq!(data.daily[2].condition, WeatherCondition::Fog);
+    }
+
+    #[test]
+    fn converts_to_imperial_units() {
+        let data = weather_data(&fixture(), chrono_tz::Europe::Oslo, Units::Imperial).unwrap();
+
+        assert_close(data.snapshot.temperature, 7.2 * 9.0 / 5.0 + 32.0);
+        assert_close(data.snapshot.metrics.wind_speed.unwrap(), 3.4 * 2.236_936);
+        assert_close(data.daily[0].precipitation_sum.unwrap(), 1.8 / 25.4);
+        assert_close(data.daily[1].temperature_min, 4.9 * 9.0 / 5.0 + 32.0);
+    }
+
+    #[test]
+    fn maps_symbol_codes() {
+        for (symbol, condition) in [
+            ("fair_polartwilight", WeatherCondition::MainlyClear),
+            (
+                "lightrainshowers_night",
+                WeatherCondition::RainShowers(Intensity::Light),
+            ),
+            ("heavysnow", WeatherCondition::Snowfall(Intensity::Heavy)),
+            (
+                "sleetshowers_day",
+                WeatherCondition::FreezingRain(Intensity::Moderate),
+            ),
+            ("rainandthunder", WeatherCondition::Thunderstorm),
+        ] {
+            assert_eq!(condition_from_symbol(symbol), condition, "{symbol}");
+        }
+    }
+}
@@ -0,0 +1,371 @@
+use anyhow::{Context, bail};
+use chrono::{DateTime, FixedOffset};
+use chrono_tz::Tz;
+use futures::future::BoxFuture;
+use reqwest::Client;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+
+use super::{ProviderKind, Sample, WeatherProvider, apparent_temperature, daily_from_samples};
+use crate::condition::{Intensity, WeatherCondition};
+use crate::units::Units;
+use crate::{Coordinates, CurrentMetrics, WeatherData, WeatherQuery, WeatherSnapshot};
+
+/// The US National Weather Service API (weather.gov). Only covers US
+/// locations; the point lookup fails anywhere else.
+pub struct Nws {
+    pub(super) http: Client,
+    pub(super) base_url: String,
+}
+
+impl WeatherProvider for Nws {
+    fn kind(&self) -> ProviderKind {
+        ProviderKind::Nws
+    }
+
+    fn fetch(
+        &self,
+        coords: Coordinates,
+        query: WeatherQuery,
+    ) -> BoxFuture<'_, anyhow::Result<WeatherData>> {
+        Box::pin(self.fetch_weather_data(coords, query))
+    }
+}
+
+impl Nws {
+    async fn fetch_weather_data(
+        &self,
+        coords: Coordinates,
+        query: WeatherQuery,
+    ) -> anyhow::Result<WeatherData> {
+        // The point lookup resolves coordinates to a forecast grid cell
+        let point: PointResponse = self
+            .get(format!(
+                "{}/points/{:.4},{:.4}",
+                self.base_url, coords.latitude, coords.longitude
+            ))
+            .await?;
+        let timezone: Tz =
+            point.properties.time_zone.parse().map_err(|_| {
+                anyhow::anyhow!("unknown timezone `{}`", point.properties.time_zone)
+            })?;
+
+        let forecast: HourlyResponse = self
+            .get(format!("{}?units=si", point.properties.forecast_hourly))
+            .await?;
+
+        weather_data(&forecast, timezone, query.units)
+    }
+
+    async fn get<T: DeserializeOwned>(&self, url: String) -> anyhow::Result<T> {
+        self.http
+            .get(url)
+            .header("Accept", "application/geo+json")
+            .send()
+            .await
+            .context("failed to call weather.gov API")?
+            .error_for_status()
+            .context("weather.gov API returned an error")?
+            .json()
+            .await
+            .context("failed to decode weather.gov response")
+    }
+}
+
+/// Maps an hourly forecast, with times in `timezone`.
+fn weather_data(
+    forecast: &HourlyResponse,
+    timezone: Tz,
+    units: Units,
+) -> anyhow::Result<WeatherData> {
+    let samples: Vec<Sample> = forecast
+        .properties
+        .periods
+        .iter()
+        .map(|period| Sample {
+            time: period.start_time.with_timezone(&timezone),
+            temperature: units.temperature_from_celsius(period.celsius()),
+            precipitation: None,
+            precipitation_probability: period
+                .probability_of_precipitation
+                .as_ref()
+                .and_then(|value| value.value),
+            condition: condition_from_icon(&period.icon),
+            is_day: !period.icon.contains("/night/"),
+        })
+        .collect();
+
+    let Some(first) = forecast.properties.periods.first() else {
+        bail!("weather.gov forecast has no periods");
+    };
+    let humidity_pct = first
+        .relative_humidity
+        .as_ref()
+        .and_then(|value| value.value)
+        .unwrap_or_default();
+    let snapshot = WeatherSnapshot {
+        temperature: samples[0].temperature,
+        feels_like: units.temperature_from_celsius(apparent_temperature(
+            first.celsius(),
+            humidity_pct,
+            first.wind_speed_ms(),
+        )),
+        humidity_pct,
+        condition: samples[0].condition,
+        is_day: samples[0].is_day,
+        observation_time: Some(samples[0].time),
+        metrics: CurrentMetrics {
+            wind_speed: first
+                .wind_speed
+                .is_some()
+                .then(|| units.wind_speed_from_ms(first.wind_speed_ms())),
+            wind_direction: first.wind_direction.as_deref().and_then(compass_degrees),
+            dew_point: first
+                .dewpoint
+                .as_ref()
+                .and_then(|value| value.value)
+                .map(|celsius| units.temperature_from_celsius(celsius)),
+            ..CurrentMetrics::default()
+        },
+    };
+
+    Ok(WeatherData {
+        snapshot,
+        hourly: samples.iter().map(Sample::hourly).collect(),
+        minutely: Vec::new(),
+        daily: daily_from_samples(&samples),
+        timezone,
+    })
+}
+
+/// Maps the first condition in an icon URL such as
+/// `https://api.weather.gov/icons/land/day/tsra_hi,20/rain,40?size=small`.
+fn condition_from_icon(icon: &str) -> WeatherCondition {
+    let code = icon
+        .split_once("/day/")
+        .or_else(|| icon.split_once("/night/"))
+        .and_then(|(_, rest)| rest.split([',', '/', '?']).next())
+        .unwrap_or_default();
+
+    match code {
+        "skc" | "wind_skc" | "hot" | "cold" => WeatherCondition::Clear,
+        "few" | "wind_few" => WeatherCondition::MainlyClear,
+        "sct" | "wind_sct" => WeatherCondition::PartlyCloudy,
+        "bkn" | "ovc" | "wind_bkn" | "wind_ovc" => WeatherCondition::Overcast,
+        "fog" | "haze" | "smoke" | "dust" => WeatherCondition::Fog,
+        "rain" => WeatherCondition::Rain(Intensity::Moderate),
+        "rain_showers_hi" => WeatherCondition::RainShowers(Intensity::Light),
+        "rain_showers" => WeatherCondition::RainShowers(Intensity::Moderate),
+        "snow" => WeatherCondition::Snowfall(Intensity::Moderate),
+        "blizzard" => WeatherCondition::Snowfall(Intensity::Heavy),
+        "rain_snow" | "rain_sleet" | "snow_sleet" | "sleet" | "fzra" | "rain_fzra"
+        | "snow_fzra" => WeatherCondition::FreezingRain(Intensity::Moderate),
+        "tsra" | "tsra_sct" | "tsra_hi" | "tornado" | "hurricane" | "tropical_storm" => {
+            WeatherCondition::Thunderstorm
+        }
+        _ => WeatherCondition::Unknown,
+    }
+}
+
+#[derive(Deserialize)]
+struct PointResponse {
+    properties: PointProperties,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PointProperties {
+    forecast_hourly: String,
+    time_zone: String,
+}
+
+#[derive(Deserialize)]
+struct HourlyResponse {
+    properties: HourlyProperties,
+}
+
+#[derive(Deserialize)]
+struct HourlyProperties {
+    periods: Vec<Period>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Period {
+    start_time: DateTime<FixedOffset>,
+    temperature: f64,
+    temperature_unit: String,
+    probability_of_precipitation: Option<QuantitativeValue>,
+    relative_humidity: Option<QuantitativeValue>,
+    /// Free text such as "10 km/h" or "5 to 10 mph".
+    wind_speed: Option<String>,
+    /// Sixteen-point compass direction such as "SSW".
+    wind_direction: Option<String>,
+    /// Degrees Celsius with `units=si`.
+    dewpoint: Option<QuantitativeValue>,
+    icon: String,
+}
+
+impl Period {
+    fn celsius(&self) -> f64 {
+        match self.temperature_unit.as_str() {
+            "F" => (self.temperature - 32.0) * 5.0 / 9.0,
+            _ => self.temperature,
+        }
+    }
+
+    /// Upper end of the wind speed range in m/s, 0 if it can't be parsed.
+    fn wind_speed_ms(&self) -> f64 {
+        let Some(text) = self.wind_speed.as_deref() else {
+            return 0.0;
+        };
+        let speed = text
+            .split_whitespace()
+            .rev()
+            .find_map(|word| word.parse::<f64>().ok())
+            .unwrap_or_default();
+        if text.ends_with("mph") {
+            speed * 0.447_04
+        } else {
+            speed / 3.6
+        }
+    }
+}
+
+/// Bearing of a sixteen-point compass direction such as "SSW".
+fn compass_degrees(direction: &str) -> Option<f64> {
+    const POINTS: [&str; 16] = [
+        "N", "NNE", "NE", "ENE", "E", "ESE", "SE", "SSE", "S", "SSW", "SW", "WSW", "W", "WNW",
+        "NW", "NNW",
+    ];
+    POINTS
+        .iter()
+        .position(|point| *point == direction)
+        .map(|index| index as f64 * 22.5)
+}
+
+#[derive(Deserialize)]
+struct QuantitativeValue {
+    value: Option<f64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, Timelike};
+
+    use super::*;
+    use crate::provider::assert_close;
+
+    fn fixture(units: Units) -> WeatherData {
+        let forecast: HourlyResponse =
+            serde_json::from_str(include_str!("../../tests/fixtures/nws_hourly.json")).unwrap();
+        weather_data(&forecast, chrono_tz::America::New_York, units).unwrap()
+    }
+
+    fn fahrenheit(celsius: f64) -> f64 {
+        celsius * 9.0 / 5.0 + 32.0
+    }
+
+    #[test]
+    fn reads_the_point_lookup() {
+        let point: PointResponse =
+            serde_json::from_str(include_str!("../../tests/fixtures/nws_point.json")).unwrap();
+
+        assert_eq!(point.properties.time_zone, "America/New_York");
+        assert_eq!(
+            point.properties.forecast_hourly,
+            "https://api.weather.gov/gridpoints/OKX/33,35/forecast/hourly"
+        );
+    }
+
+    #[test]
+    fn maps_current_conditions() {
+        let snapshot = fixture(Units::Imperial).snapshot;
+
+        assert_close(snapshot.temperature, fahrenheit(11.1));
+        assert_close(snapshot.humidity_pct, 81.0);
+        assert_eq!(snapshot.condition, WeatherCondition::Overcast);
+        assert!(!snapshot.is_day);
+        assert_eq!(
+            snapshot.observation_time.unwrap().to_rfc3339(),
+            "2026-10-31T22:00:00-04:00"
+        );
+        // "13 km/h" from the SW
+        assert_close(snapshot.metrics.wind_speed.unwrap(), 13.0 / 3.6 * 2.236_936);
+        assert_eq!(snapshot.metrics.wind_direction, Some(225.0));
+        assert_close(snapshot.metrics.dew_point.unwrap(), fahrenheit(7.8));
+    }
+
+    #[test]
+    fn keeps_the_repeated_hour_when_dst_ends() {
+        let data = fixture(Units::Metric);
+        let repeated: Vec<String> = data
+            .hourly
+            .iter()
+            .filter(|step| step.time.hour() == 1)
+            .map(|step| step.time.to_rfc3339())
+            .collect();
+
+        assert_eq!(
+            repeated,
+            ["2026-11-01T01:00:00-04:00", "2026-11-01T01:00:00-05:00"]
+        );
+    }
+
+    #[test]
+    fn aggregates_hours_into_days() {
+        let data = fixture(Units::Imperial);
+        let dates: Vec<NaiveDate> = data.daily.iter().map(|day| day.date).collect();
+        assert_eq!(
+            dates,
+            [
+                NaiveDate::from_ymd_opt(2026, 10, 31).unwrap(),
+                NaiveDate::from_ymd_opt(2026, 11, 1).unwrap(),
+            ]
+        );
+
+        let saturday = &data.daily[0];
+        assert_close(saturday.temperature_max, fahrenheit(11.1));
+        assert_close(saturday.temperature_min, fahrenheit(10.6));
+        assert_eq!(saturday.precipitation_probability, Some(35.0));
+        assert_eq!(
+            saturday.condition,
+            WeatherCondition::RainShowers(Intensity::Moderate)
+        );
+
+        let sunday = &data.daily[1];
+        assert_close(sunday.temperature_max, fahrenheit(10.0));
+        assert_close(sunday.temperature_min, fahrenheit(8.3));
+        assert_eq!(sunday.precipitation_probability, Some(60.0));
+        // No amounts in the hourly forecast, only probabilities
+        assert_eq!(sunday.precipitation_sum, None);
+        assert_eq!(
+            sunday.condition,
+            WeatherCondition::Rain(Intensity::Moderate)
+        );
+    }
+
+    #[test]
+    fn maps_icon_urls() {
+        for (icon, condition) in [
+            (
+                "https://api.weather.gov/icons/land/day/tsra_hi,20/rain,40?size=small",
+                WeatherCondition::Thunderstorm,
+            ),
+            (
+                "https://api.weather.gov/icons/land/night/rain_showers,30/fog?size=small",
+                WeatherCondition::RainShowers(Intensity::Moderate),
+            ),
+            (
+                "https://api.weather.gov/icons/land/day/blizzard?size=small",
+                WeatherCondition::Snowfall(Intensity::Heavy),
+            ),
+            (
+                "https://api.weather.gov/icons/land/day/skc?size=small",
+                WeatherCondition::Clear,
+            ),
+        ] {
+            assert_eq!(condition_from_icon(icon), condition, "{icon}");
+        }
+    }
+}
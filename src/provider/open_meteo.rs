@@ -0,0 +1,361 @@
+use anyhow::Context;
+use chrono::{Duration, TimeZone, Utc};
+use chrono_tz::Tz;
+use futures::future::BoxFuture;
+use reqwest::Client;
+use serde::Deserialize;
+use tracing::warn;
+
+use super::{ProviderKind, WeatherProvider};
+use crate::condition::WeatherCondition;
+use crate::{
+    Coordinates, CurrentMetrics, DailyForecast, HourlyForecast, MinutelyForecast, WeatherData,
+    WeatherQuery, WeatherSnapshot,
+};
+
+/// 15-minute steps requested for the nowcast: its two hour window plus an
+/// hour of slack for cached responses.
+const MINUTELY_STEPS: u32 = 12;
+/// Hours the pressure trend looks back; requested as past hourly data so the
+/// trend exists right after local midnight too.
+const TREND_HOURS: i64 = 3;
+
+pub struct OpenMeteo {
+    pub(super) http: Client,
+    pub(super) base_url: String,
+}
+
+impl WeatherProvider for OpenMeteo {
+    fn kind(&self) -> ProviderKind {
+        ProviderKind::OpenMeteo
+    }
+
+    fn fetch(
+        &self,
+        coords: Coordinates,
+        query: WeatherQuery,
+    ) -> BoxFuture<'_, anyhow::Result<WeatherData>> {
+        Box::pin(self.fetch_weather_data(coords, query))
+    }
+}
+
+impl OpenMeteo {
+    async fn fetch_weather_data(
+        &self,
+        coords: Coordinates,
+        query: WeatherQuery,
+    ) -> anyhow::Result<WeatherData> {
+        let units = query.units;
+        let url = format!(
+            "{}/v1/forecast?latitude={}&longitude={}&current=temperature_2m,apparent_temperature,relative_humidity_2m,weather_code,is_day,wind_speed_10m,wind_direction_10m,wind_gusts_10m,surface_pressure,uv_index,visibility,dew_point_2m,cloud_cover&hourly=temperature_2m,precipitation_probability,surface_pressure&minutely_15=precipitation&forecast_minutely_15={}&daily=weather_code,temperature_2m_max,temperature_2m_min,precipitation_sum,precipitation_probability_max,sunrise,sunset&forecast_days={}&past_hours={}&temperature_unit={}&wind_speed_unit={}&precipitation_unit={}&timeformat=unixtime&timezone=auto",
+            self.base_url,
+            coords.latitude,
+            coords.longitude,
+            MINUTELY_STEPS,
+            query.forecast_days,
+            TREND_HOURS,
+            units.temperature_param(),
+            units.wind_speed_param(),
+            units.precipitation_param(),
+        );
+
+        let response: OpenMeteoResponse = self
+            .http
+            .get(url)
+            .send()
+            .await
+            .context("failed to call weather API")?
+            .error_for_status()
+            .context("weather API returned an error")?
+            .json()
+            .await
+            .context("failed to decode weather API response")?;
+
+        // `timezone=auto` makes Open-Meteo report the location's IANA zone
+        let timezone = match response.timezone.as_deref().map(str::parse::<Tz>) {
+            Some(Ok(tz)) => tz,
+            Some(Err(_)) | None => {
+                warn!(
+                    timezone = ?response.timezone,
+                    "weather API returned no usable timezone; using UTC"
+                );
+                Tz::UTC
+            }
+        };
+
+        let observation_time = response
+            .current
+            .time
+            .and_then(|ts| Utc.timestamp_opt(ts, 0).latest())
+            .map(|dt| dt.with_timezone(&timezone));
+
+        let current = &response.current;
+        let snapshot = WeatherSnapshot {
+            temperature: current.temperature_2m,
+            feels_like: current.apparent_temperature,
+            humidity_pct: current.relative_humidity_2m,
+            condition: WeatherCondition::from_wmo(current.weather_code),
+            is_day: current.is_day != 0,
+            observation_time,
+            metrics: CurrentMetrics {
+                wind_speed: current.wind_speed_10m,
+                wind_direction: current.wind_direction_10m,
+                wind_gusts: current.wind_gusts_10m,
+                pressure: current.surface_pressure,
+                pressure_trend: self.pressure_trend(&response),
+                uv_index: current.uv_index,
+                visibility: current.visibility.map(|visibility| {
+                    // Reported in feet alongside imperial units
+                    match response.current_units.visibility.as_deref() {
+                        Some("ft") => visibility * 0.3048,
+                        _ => visibility,
+                    }
+                }),
+                dew_point: current.dew_point_2m,
+                cloud_cover_pct: current.cloud_cover,
+            },
+        };
+
+        let hourly = self.collect_hourly_forecast(&response, timezone);
+        let minutely = self.collect_minutely_forecast(&response, timezone);
+        let daily = self.collect_daily_forecast(&response, timezone);
+
+        Ok(WeatherData {
+            snapshot,
+            hourly,
+            minutely,
+            daily,
+            timezone,
+        })
+    }
+
+    /// Current surface pressure minus the hourly value `TREND_HOURS` earlier.
+    fn pressure_trend(&self, response: &OpenMeteoResponse) -> Option<f64> {
+        let current = response.current.surface_pressure?;
+        let hourly = response.hourly.as_ref()?;
+        let target = response.current.time? - Duration::hours(TREND_HOURS).num_seconds();
+
+        hourly
+            .time
+            .iter()
+            .zip(hourly.surface_pressure.iter())
+            .find(|(time, _)| (**time - target).abs() < Duration::minutes(30).num_seconds())
+            .and_then(|(_, pressure)| *pressure)
+            .map(|earlier| current - earlier)
+    }
+
+    fn collect_daily_forecast(
+        &self,
+        response: &OpenMeteoResponse,
+        timezone: Tz,
+    ) -> Vec<DailyForecast> {
+        let Some(daily) = response.daily.as_ref() else {
+            return Vec::new();
+        };
+        let to_local = |ts: i64| {
+            Utc.timestamp_opt(ts, 0)
+                .latest()
+                .map(|dt| dt.with_timezone(&timezone))
+        };
+
+        daily
+            .time
+            .iter()
+            .enumerate()
+            .filter_map(|(index, time)| {
+                Some(DailyForecast {
+                    // Daily timestamps are local midnight of each day
+                    date: to_local(*time)?.date_naive(),
+                    condition: WeatherCondition::from_wmo(*daily.weather_code.get(index)?),
+                    temperature_max: *daily.temperature_2m_max.get(index)?,
+                    temperature_min: *daily.temperature_2m_min.get(index)?,
+                    precipitation_sum: daily.precipitation_sum.get(index).copied().flatten(),
+                    precipitation_probability: daily
+                        .precipitation_probability_max
+                        .get(index)
+                        .copied()
+                        .flatten(),
+                    sunrise: daily.sunrise.get(index).and_then(|ts| to_local(*ts)),
+                    sunset: daily.sunset.get(index).and_then(|ts| to_local(*ts)),
+                })
+            })
+            .collect()
+    }
+
+    /// Steps the model has no value for are dropped; outside its
+    /// 15-minute domains Open-Meteo interpolates hourly data instead.
+    fn collect_minutely_forecast(
+        &self,
+        response: &OpenMeteoResponse,
+        timezone: Tz,
+    ) -> Vec<MinutelyForecast> {
+        let Some(minutely) = response.minutely_15.as_ref() else {
+            return Vec::new();
+        };
+
+        minutely
+            .time
+            .iter()
+            .zip(minutely.precipitation.iter())
+            .filter_map(|(time, precipitation)| {
+                let timestamp = Utc.timestamp_opt(*time, 0).latest()?;
+                Some(MinutelyForecast {
+                    time: timestamp.with_timezone(&timezone),
+                    precipitation: (*precipitation)?,
+                })
+            })
+            .collect()
+    }
+
+    fn collect_hourly_forecast(
+        &self,
+        response: &OpenMeteoResponse,
+        timezone: Tz,
+    ) -> Vec<HourlyForecast> {
+        let Some(hourly) = response.hourly.as_ref() else {
+            return Vec::new();
+        };
+
+        hourly
+            .time
+            .iter()
+            .zip(hourly.temperature_2m.iter())
+            .zip(hourly.precipitation_probability.iter())
+            .filter_map(|((time, temp), precipitation)| {
+                let timestamp = Utc.timestamp_opt(*time, 0).latest()?;
+                Some(HourlyForecast {
+                    time: timestamp.with_timezone(&timezone),
+                    temperature: *temp,
+                    precipitation_probability: *precipitation,
+                })
+            })
+            .collect()
+    }
+}
+
+#[derive(Deserialize)]
+struct OpenMeteoResponse {
+    timezone: Option<String>,
+    current: OpenMeteoCurrent,
+    #[serde(default)]
+    current_units: OpenMeteoCurrentUnits,
+    hourly: Option<OpenMeteoHourly>,
+    minutely_15: Option<OpenMeteoMinutely>,
+    daily: Option<OpenMeteoDaily>,
+}
+
+#[derive(Deserialize)]
+struct OpenMeteoCurrent {
+    temperature_2m: f64,
+    apparent_temperature: f64,
+    relative_humidity_2m: f64,
+    weather_code: i32,
+    /// 1 between sunrise and sunset, 0 otherwise.
+    is_day: u8,
+    time: Option<i64>,
+    wind_speed_10m: Option<f64>,
+    wind_direction_10m: Option<f64>,
+    wind_gusts_10m: Option<f64>,
+    surface_pressure: Option<f64>,
+    uv_index: Option<f64>,
+    visibility: Option<f64>,
+    dew_point_2m: Option<f64>,
+    cloud_cover: Option<f64>,
+}
+
+#[derive(Default, Deserialize)]
+struct OpenMeteoCurrentUnits {
+    visibility: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct OpenMeteoHourly {
+    time: Vec<i64>,
+    temperature_2m: Vec<f64>,
+    precipitation_probability: Vec<Option<f64>>,
+    #[serde(default)]
+    surface_pressure: Vec<Option<f64>>,
+}
+
+#[derive(Deserialize)]
+struct OpenMeteoMinutely {
+    time: Vec<i64>,
+    precipitation: Vec<Option<f64>>,
+}
+
+#[derive(Deserialize)]
+struct OpenMeteoDaily {
+    time: Vec<i64>,
+    weather_code: Vec<i32>,
+    temperature_2m_max: Vec<f64>,
+    temperature_2m_min: Vec<f64>,
+    precipitation_sum: Vec<Option<f64>>,
+    precipitation_probability_max: Vec<Option<f64>>,
+    sunrise: Vec<i64>,
+    sunset: Vec<i64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    use axum::Router;
+    use axum::extract::Query;
+    use axum::routing::get;
+
+    use super::*;
+    use crate::provider::assert_close;
+    use crate::units::Units;
+
+    #[tokio::test]
+    async fn pressure_trend_reaches_back_past_midnight() {
+        let queries = Arc::new(Mutex::new(Vec::new()));
+        let handler = {
+            let queries = queries.clone();
+            move |Query(query): Query<HashMap<String, String>>| async move {
+                queries.lock().unwrap().push(query);
+                // 01:00 in Berlin, with hourly data from 22:00 the day before
+                r#"{
+                    "timezone": "Europe/Berlin",
+                    "current": {
+                        "time": 1792191600,
+                        "temperature_2m": 8.0,
+                        "apparent_temperature": 6.0,
+                        "relative_humidity_2m": 80,
+                        "weather_code": 0,
+                        "is_day": 0,
+                        "surface_pressure": 1009.5
+                    },
+                    "hourly": {
+                        "time": [1792180800, 1792184400, 1792188000, 1792191600],
+                        "temperature_2m": [10.0, 9.4, 8.7, 8.0],
+                        "precipitation_probability": [0, 0, 0, 0],
+                        "surface_pressure": [1012.0, 1011.2, 1010.3, 1009.5]
+                    }
+                }"#
+            }
+        };
+        let app = Router::new().route("/v1/forecast", get(handler));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let provider = OpenMeteo {
+            http: Client::new(),
+            base_url,
+        };
+        let coords = Coordinates {
+            latitude: 52.52,
+            longitude: 13.41,
+        };
+        let query = WeatherQuery {
+            units: Units::Metric,
+            forecast_days: 3,
+        };
+        let data = provider.fetch(coords, query).await.unwrap();
+
+        assert_eq!(queries.lock().unwrap()[0]["past_hours"], "3");
+        assert_close(data.snapshot.metrics.pressure_trend.unwrap(), -2.5);
+    }
+}
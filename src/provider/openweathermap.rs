@@ -0,0 +1,370 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use chrono::{TimeZone, Utc};
+use chrono_tz::Tz;
+use futures::future::BoxFuture;
+use reqwest::Client;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+
+use super::{
+    ProviderKind, Sample, TimezoneLookup, WeatherProvider, daily_from_samples, lookup_fallback,
+    offset_timezone,
+};
+use crate::condition::{Intensity, WeatherCondition};
+use crate::units::Units;
+use crate::{Coordinates, CurrentMetrics, WeatherData, WeatherQuery, WeatherSnapshot};
+
+/// OpenWeatherMap's free current weather and 5 day / 3 hour forecast APIs.
+pub struct OpenWeatherMap {
+    pub(super) http: Client,
+    pub(super) base_url: String,
+    pub(super) api_key: Option<String>,
+    /// The config's `timezone`; looked up when unset.
+    pub(super) timezone: Option<Tz>,
+    pub(super) timezones: Arc<TimezoneLookup>,
+}
+
+impl WeatherProvider for OpenWeatherMap {
+    fn kind(&self) -> ProviderKind {
+        ProviderKind::OpenWeatherMap
+    }
+
+    fn fetch(
+        &self,
+        coords: Coordinates,
+        query: WeatherQuery,
+    ) -> BoxFuture<'_, anyhow::Result<WeatherData>> {
+        Box::pin(self.fetch_weather_data(coords, query))
+    }
+}
+
+impl OpenWeatherMap {
+    async fn fetch_weather_data(
+        &self,
+        coords: Coordinates,
+        query: WeatherQuery,
+    ) -> anyhow::Result<WeatherData> {
+        let api_key = self
+            .api_key
+            .as_deref()
+            .context("OpenWeatherMap API key is not configured")?;
+
+        let (responses, timezone) = tokio::join!(
+            async {
+                tokio::try_join!(
+                    self.get::<CurrentResponse>("weather", coords, api_key),
+                    self.get::<ForecastResponse>("forecast", coords, api_key),
+                )
+            },
+            self.timezones.resolve_configured(self.timezone, coords),
+        );
+        let (current, forecast) = responses?;
+
+        // Only the current offset is reported, which goes wrong across a
+        // DST change and for half-hour zones, so the zone is looked up
+        let timezone = timezone
+            .unwrap_or_else(|err| lookup_fallback(err, offset_timezone(forecast.city.timezone)));
+
+        Ok(weather_data(&current, &forecast, timezone, query.units))
+    }
+
+    async fn get<T: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        coords: Coordinates,
+        api_key: &str,
+    ) -> anyhow::Result<T> {
+        // The key goes in the query rather than the URL string, and URLs are
+        // stripped from errors, so failures never log it
+        self.http
+            .get(format!("{}/data/2.5/{endpoint}", self.base_url))
+            .query(&[("lat", coords.latitude), ("lon", coords.longitude)])
+            .query(&[("appid", api_key), ("units", "metric")])
+            .send()
+            .await
+            .map_err(reqwest::Error::without_url)
+            .with_context(|| format!("failed to call OpenWeatherMap {endpoint} API"))?
+            .error_for_status()
+            .map_err(reqwest::Error::without_url)
+            .with_context(|| format!("OpenWeatherMap {endpoint} API returned an error"))?
+            .json()
+            .await
+            .map_err(reqwest::Error::without_url)
+            .with_context(|| format!("failed to decode OpenWeatherMap {endpoint} response"))
+    }
+}
+
+/// Maps the current and forecast responses, with times in `timezone`.
+fn weather_data(
+    current: &CurrentResponse,
+    forecast: &ForecastResponse,
+    timezone: Tz,
+    units: Units,
+) -> WeatherData {
+    let to_local = |ts: i64| {
+        Utc.timestamp_opt(ts, 0)
+            .latest()
+            .map(|dt| dt.with_timezone(&timezone))
+    };
+
+    let samples: Vec<Sample> = forecast
+        .list
+        .iter()
+        .filter_map(|entry| {
+            let precipitation = [&entry.rain, &entry.snow]
+                .into_iter()
+                .flatten()
+                .filter_map(|volume| volume.three_hours)
+                .reduce(|total, mm| total + mm);
+            Some(Sample {
+                time: to_local(entry.dt)?,
+                temperature: units.temperature_from_celsius(entry.main.temp),
+                precipitation: precipitation.map(|mm| units.precipitation_from_mm(mm)),
+                precipitation_probability: entry.pop.map(|pop| pop * 100.0),
+                condition: condition_from_id(&entry.weather),
+                is_day: is_day(&entry.weather),
+            })
+        })
+        .collect();
+
+    let snapshot = WeatherSnapshot {
+        temperature: units.temperature_from_celsius(current.main.temp),
+        feels_like: units.temperature_from_celsius(current.main.feels_like),
+        humidity_pct: current.main.humidity,
+        condition: condition_from_id(&current.weather),
+        is_day: is_day(&current.weather),
+        observation_time: to_local(current.dt),
+        metrics: CurrentMetrics {
+            wind_speed: current.wind.speed.map(|ms| units.wind_speed_from_ms(ms)),
+            wind_direction: current.wind.deg,
+            wind_gusts: current.wind.gust.map(|ms| units.wind_speed_from_ms(ms)),
+            pressure: current.main.grnd_level.or(current.main.pressure),
+            pressure_trend: None,
+            uv_index: None,
+            visibility: current.visibility,
+            dew_point: None,
+            cloud_cover_pct: current.clouds.all,
+        },
+    };
+
+    WeatherData {
+        snapshot,
+        hourly: samples.iter().map(Sample::hourly).collect(),
+        minutely: Vec::new(),
+        daily: daily_from_samples(&samples),
+        timezone,
+    }
+}
+
+/// Maps the primary OpenWeatherMap condition id.
+fn condition_from_id(conditions: &[Condition]) -> WeatherCondition {
+    let Some(condition) = conditions.first() else {
+        return WeatherCondition::Unknown;
+    };
+    let intensity = match condition.id {
+        300 | 310 | 500 | 520 | 600 | 612 | 615 | 620 => Intensity::Light,
+        302 | 312 | 314 | 502..=504 | 522 | 602 | 622 => Intensity::Heavy,
+        _ => Intensity::Moderate,
+    };
+
+    match condition.id {
+        200..=232 => WeatherCondition::Thunderstorm,
+        300..=321 => WeatherCondition::Drizzle(intensity),
+        500..=504 => WeatherCondition::Rain(intensity),
+        511 | 611..=616 => WeatherCondition::FreezingRain(intensity),
+        520..=531 => WeatherCondition::RainShowers(intensity),
+        600..=602 => WeatherCondition::Snowfall(intensity),
+        620..=622 => WeatherCondition::SnowShowers(intensity),
+        701..=781 => WeatherCondition::Fog,
+        800 => WeatherCondition::Clear,
+        801 => WeatherCondition::MainlyClear,
+        802 => WeatherCondition::PartlyCloudy,
+        803 | 804 => WeatherCondition::Overcast,
+        _ => WeatherCondition::Unknown,
+    }
+}
+
+/// Icon codes end in `d` or `n`, e.g. `10n` for rain at night.
+fn is_day(conditions: &[Condition]) -> bool {
+    conditions
+        .first()
+        .is_none_or(|condition| !condition.icon.ends_with('n'))
+}
+
+#[derive(Deserialize)]
+struct CurrentResponse {
+    dt: i64,
+    main: Main,
+    weather: Vec<Condition>,
+    #[serde(default)]
+    wind: Wind,
+    #[serde(default)]
+    clouds: Clouds,
+    /// Metres, capped at 10 km.
+    visibility: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct ForecastResponse {
+    list: Vec<ForecastEntry>,
+    city: City,
+}
+
+#[derive(Deserialize)]
+struct ForecastEntry {
+    dt: i64,
+    main: Main,
+    weather: Vec<Condition>,
+    /// Probability of precipitation, 0 to 1.
+    pop: Option<f64>,
+    rain: Option<Volume>,
+    snow: Option<Volume>,
+}
+
+#[derive(Deserialize)]
+struct Main {
+    temp: f64,
+    feels_like: f64,
+    humidity: f64,
+    /// Sea level pressure in hPa.
+    pressure: Option<f64>,
+    /// Surface pressure in hPa.
+    grnd_level: Option<f64>,
+}
+
+#[derive(Default, Deserialize)]
+struct Wind {
+    speed: Option<f64>,
+    deg: Option<f64>,
+    gust: Option<f64>,
+}
+
+#[derive(Default, Deserialize)]
+struct Clouds {
+    /// Cloud cover in percent.
+    all: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct Condition {
+    id: i32,
+    icon: String,
+}
+
+#[derive(Deserialize)]
+struct Volume {
+    #[serde(rename = "3h")]
+    three_hours: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct City {
+    /// Offset from UTC in seconds.
+    timezone: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::provider::assert_close;
+
+    fn fixture(units: Units) -> WeatherData {
+        let current: CurrentResponse = serde_json::from_str(include_str!(
+            "../../tests/fixtures/openweathermap_current.json"
+        ))
+        .unwrap();
+        let forecast: ForecastResponse = serde_json::from_str(include_str!(
+            "../../tests/fixtures/openweathermap_forecast.json"
+        ))
+        .unwrap();
+        weather_data(&current, &forecast, chrono_tz::Europe::Berlin, units)
+    }
+
+    #[test]
+    fn maps_current_conditions() {
+        let snapshot = fixture(Units::Metric).snapshot;
+
+        assert_close(snapshot.temperature, 8.61);
+        assert_close(snapshot.feels_like, 6.3);
+        assert_close(snapshot.humidity_pct, 87.0);
+        assert_eq!(snapshot.condition, WeatherCondition::Rain(Intensity::Light));
+        assert!(!snapshot.is_day);
+        assert_eq!(
+            snapshot.observation_time.unwrap().to_rfc3339(),
+            "2026-10-24T22:40:00+02:00"
+        );
+        assert_close(snapshot.metrics.wind_speed.unwrap(), 4.12 * 3.6);
+        // Surface pressure is preferred over the sea level value
+        assert_eq!(snapshot.metrics.pressure, Some(999.0));
+        assert_eq!(snapshot.metrics.visibility, Some(10000.0));
+        assert_eq!(snapshot.metrics.cloud_cover_pct, Some(100.0));
+    }
+
+    #[test]
+    fn keeps_local_time_across_the_end_of_dst() {
+        let data = fixture(Units::Metric);
+        let local: Vec<String> = data
+            .hourly
+            .iter()
+            .map(|step| step.time.format("%d %H:%M %:z").to_string())
+            .collect();
+
+        // The fixed +02:00 offset the API reports would be wrong from 01:00 UTC
+        assert_eq!(
+            local,
+            [
+                "24 23:00 +02:00",
+                "25 02:00 +02:00",
+                "25 04:00 +01:00",
+                "25 07:00 +01:00",
+                "25 10:00 +01:00",
+                "25 13:00 +01:00",
+                "25 16:00 +01:00",
+                "25 19:00 +01:00",
+                "25 22:00 +01:00",
+                "26 01:00 +01:00",
+            ]
+        );
+        assert_eq!(data.hourly[0].precipitation_probability, Some(62.0));
+    }
+
+    #[test]
+    fn aggregates_three_hour_steps_into_days() {
+        let data = fixture(Units::Metric);
+        let dates: Vec<NaiveDate> = data.daily.iter().map(|day| day.date).collect();
+        assert_eq!(
+            dates,
+            [
+                NaiveDate::from_ymd_opt(2026, 10, 24).unwrap(),
+                NaiveDate::from_ymd_opt(2026, 10, 25).unwrap(),
+                NaiveDate::from_ymd_opt(2026, 10, 26).unwrap(),
+            ]
+        );
+
+        let sunday = &data.daily[1];
+        assert_close(sunday.temperature_max, 11.3);
+        assert_close(sunday.temperature_min, 6.1);
+        // Rain and snow volumes both count
+        assert_close(sunday.precipitation_sum.unwrap(), 2.14 + 0.35 + 0.2 + 0.4);
+        assert_close(sunday.precipitation_probability.unwrap(), 81.0);
+        assert_eq!(
+            sunday.condition,
+            WeatherCondition::FreezingRain(Intensity::Light)
+        );
+        assert_eq!(data.daily[2].precipitation_sum, None);
+    }
+
+    #[test]
+    fn converts_to_imperial_units() {
+        let data = fixture(Units::Imperial);
+
+        assert_close(data.snapshot.temperature, 8.61 * 9.0 / 5.0 + 32.0);
+        assert_close(data.snapshot.metrics.wind_speed.unwrap(), 4.12 * 2.236_936);
+        assert_close(data.daily[0].precipitation_sum.unwrap(), 0.87 / 25.4);
+        assert_close(data.daily[1].temperature_max, 11.3 * 9.0 / 5.0 + 32.0);
+    }
+}
@@ -1,15 +1,18 @@
 use anyhow::Context;
 use chrono::{Datelike, Duration, Local, Timelike, Utc};
+use chrono_tz::Tz;
 use image::{ImageBuffer, Rgba};
 use once_cell::sync::Lazy;
 use rust_embed::RustEmbed;
 use std::path::PathBuf;
 use std::str;
 use typst::diag::{FileError, FileResult};
-use typst::foundations::{Bytes, Datetime, Dict};
+use typst::foundations::{Bytes, Datetime, Dict, Label, Selector, Str};
+use typst::introspection::MetadataElem;
+use typst::layout::PagedDocument;
 use typst::syntax::{FileId, Source, VirtualPath};
 use typst::text::{Font, FontBook};
-use typst::utils::LazyHash;
+use typst::utils::{LazyHash, PicoStr};
 use typst::{Library, LibraryExt, World};
 use typst_render::render;
 
@@ -36,9 +39,10 @@ pub fn render_widget(
     main_path: &str,
     pixel_per_pt: f32,
     inputs: Dict,
+    timezone: Option<Tz>,
 ) -> anyhow::Result<ImageBuffer<Rgba<u8>, Vec<u8>>> {
-    let world = MemoryWorld::new(document, main_path, inputs)?;
-    let warned = typst::compile::<typst::layout::PagedDocument>(&world);
+    let world = MemoryWorld::new(document, main_path, inputs, timezone)?;
+    let warned = typst::compile::<PagedDocument>(&world);
 
     if !warned.warnings.is_empty() {
         for warning in warned.warnings {
@@ -49,21 +53,57 @@ pub fn render_widget(
     let document = warned
         .output
         .map_err(|errors| anyhow::anyhow!("typst errors: {errors:?}"))?;
+    if document.pages.len() > 1 {
+        tracing::warn!(
+            pages = document.pages.len(),
+            cut_off = ?overflowing_slots(&document),
+            "dashboard doesn't fit on one page; only the first is drawn"
+        );
+    }
     let pixmap = render(&document.pages[0], pixel_per_pt);
 
     ImageBuffer::from_vec(pixmap.width(), pixmap.height(), pixmap.data().to_vec())
         .context("failed to build image buffer from typst pixmap")
 }
 
+/// Keys of the widgets that end past the first page, from the `<slot-end>`
+/// metadata the dashboard template places after each one.
+fn overflowing_slots(document: &PagedDocument) -> Vec<String> {
+    let Some(label) = Label::new(PicoStr::intern("slot-end")) else {
+        return Vec::new();
+    };
+    document
+        .introspector
+        .query(&Selector::Label(label))
+        .iter()
+        .filter(|content| {
+            content
+                .location()
+                .is_some_and(|location| document.introspector.position(location).page.get() > 1)
+        })
+        .filter_map(|content| {
+            let key = &content.to_packed::<MetadataElem>()?.value;
+            Some(key.clone().cast::<Str>().ok()?.to_string())
+        })
+        .collect()
+}
+
 struct MemoryWorld {
     source: Source,
     library: LazyHash<Library>,
     book: LazyHash<FontBook>,
     fonts: Vec<Font>,
+    /// Zone used for Typst's `datetime.today()`; the server's local time if unset.
+    timezone: Option<Tz>,
 }
 
 impl MemoryWorld {
-    fn new(source_text: &str, main_path: &str, inputs: Dict) -> anyhow::Result<Self> {
+    fn new(
+        source_text: &str,
+        main_path: &str,
+        inputs: Dict,
+        timezone: Option<Tz>,
+    ) -> anyhow::Result<Self> {
         let main_id = FileId::new(None, VirtualPath::new(main_path));
         let source = Source::new(main_id, source_text.to_string());
 
@@ -81,6 +121,7 @@ impl MemoryWorld {
             library,
             book,
             fonts,
+            timezone,
         })
     }
 
@@ -137,9 +178,10 @@ impl World for MemoryWorld {
     }
 
     fn today(&self, offset: Option<i64>) -> Option<Datetime> {
-        let now = match offset {
-            Some(hours) => Utc::now() + Duration::hours(hours),
-            None => Local::now().with_timezone(&Utc),
+        let now = match (offset, self.timezone) {
+            (Some(hours), _) => (Utc::now() + Duration::hours(hours)).naive_utc(),
+            (None, Some(tz)) => Utc::now().with_timezone(&tz).naive_local(),
+            (None, None) => Local::now().naive_local(),
         };
 
         Datetime::from_ymd_hms(
@@ -152,3 +194,24 @@ impl World for MemoryWorld {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_widgets_past_the_first_page() {
+        let source = r#"
+            #set page(width: 100pt, height: 100pt, margin: 0pt)
+            #for key in ("weather", "chart", "astronomy") {
+              block(height: 30pt)
+              [#metadata(key) <slot-end>]
+            }
+        "#;
+        let world = MemoryWorld::new(source, "/main.typ", Dict::new(), None).unwrap();
+        let document = typst::compile::<PagedDocument>(&world).output.unwrap();
+
+        assert_eq!(document.pages.len(), 2);
+        assert_eq!(overflowing_slots(&document), ["astronomy"]);
+    }
+}
@@ -0,0 +1,463 @@
+//! Upstream data the widgets are built from. Widgets list the sources they
+//! need; a render fetches each distinct one once, concurrently, and hands the
+//! results to every widget that asked for it.
+
+use std::sync::Arc;
+
+use anyhow::{Context, anyhow};
+use chrono::Utc;
+use futures::future::{BoxFuture, join_all};
+use tokio::time::Instant;
+use tracing::{error, warn};
+
+use crate::air_quality::AirQuality;
+use crate::alerts::Alert;
+use crate::cache::CachedWeather;
+use crate::config::{DashboardConfig, NamedLocation};
+use crate::history::Comparison;
+use crate::provider::WeatherProvider;
+use crate::wanikani::WanikaniKanji;
+use crate::widget::KANJI_CARDS;
+use crate::{AppState, Coordinates, DashboardData, SecondaryLocation, WeatherQuery};
+
+/// Key of a data source. Widgets naming the same key share one fetch.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Source {
+    /// Current conditions and forecasts for the dashboard's location.
+    Weather,
+    /// Yesterday and the climate normal, for the weather widget's comparison.
+    History,
+    AirQuality,
+    /// Weather for the secondary places of a location set.
+    Locations,
+    /// Pending WaniKani reviews.
+    Kanji,
+    /// Active warnings for the banner above the widgets.
+    Alerts,
+}
+
+/// What sources fetch for, and the time they have for it.
+pub struct FetchContext<'a> {
+    pub state: &'a AppState,
+    pub config: &'a DashboardConfig,
+    pub coords: Coordinates,
+    /// Secondary places of a location set, if the render is for one.
+    pub secondary: &'a [NamedLocation],
+    deadline: Instant,
+}
+
+impl<'a> FetchContext<'a> {
+    /// Starts the render's `config.render_deadline`.
+    pub fn new(
+        state: &'a AppState,
+        config: &'a DashboardConfig,
+        coords: Coordinates,
+        secondary: &'a [NamedLocation],
+    ) -> Self {
+        Self {
+            state,
+            config,
+            coords,
+            secondary,
+            deadline: Instant::now() + config.render_deadline,
+        }
+    }
+
+    /// Awaits `future` for at most `config.source_timeout`, and not past the
+    /// render deadline.
+    pub async fn within<T>(
+        &self,
+        future: impl Future<Output = anyhow::Result<T>>,
+    ) -> anyhow::Result<T> {
+        let limit = self
+            .deadline
+            .min(Instant::now() + self.config.source_timeout);
+        tokio::time::timeout_at(limit, future)
+            .await
+            .map_err(|_| anyhow!("no answer in time"))?
+    }
+}
+
+/// Data as it came from one source.
+pub enum Fetched {
+    Weather(CachedWeather),
+    History(Comparison),
+    AirQuality(AirQuality),
+    Locations(Vec<SecondaryLocation>),
+    Kanji(Vec<WanikaniKanji>),
+    Alerts(Vec<Alert>),
+}
+
+impl Fetched {
+    /// Whether nothing is missing; a location set can come back with only
+    /// some of its places.
+    fn is_complete(&self) -> bool {
+        match self {
+            Fetched::Locations(locations) => {
+                locations.iter().all(|location| location.weather.is_some())
+            }
+            _ => true,
+        }
+    }
+}
+
+pub trait DataSource: Sync {
+    fn key(&self) -> Source;
+
+    /// An extra that is simply left out when it fails, without tagging the
+    /// widgets built from it as unavailable.
+    fn is_optional(&self) -> bool {
+        false
+    }
+
+    /// Fetches the data, keeping each upstream call within the context's
+    /// timeouts.
+    fn fetch<'a>(&'a self, context: &'a FetchContext<'a>)
+    -> BoxFuture<'a, anyhow::Result<Fetched>>;
+}
+
+/// Fetches each distinct source once, concurrently. Sources that fail or run
+/// out of time are logged, left empty and, unless optional, listed in
+/// `unavailable`.
+pub async fn fetch_all(
+    context: &FetchContext<'_>,
+    sources: &[&'static dyn DataSource],
+) -> DashboardData {
+    let mut distinct: Vec<&dyn DataSource> = Vec::new();
+    for &source in sources {
+        if !distinct.iter().any(|known| known.key() == source.key()) {
+            distinct.push(source);
+        }
+    }
+
+    let results = join_all(distinct.iter().map(|source| source.fetch(context))).await;
+    let mut data = DashboardData::default();
+    for (source, result) in distinct.into_iter().zip(results) {
+        match result {
+            Ok(fetched) => {
+                if !fetched.is_complete() {
+                    data.unavailable.push(source.key());
+                }
+                data.store(fetched);
+            }
+            Err(err) if source.is_optional() => {
+                warn!(?err, source = ?source.key(), "failed to fetch; leaving it out");
+            }
+            Err(err) => {
+                error!(?err, source = ?source.key(), "failed to fetch; showing placeholders");
+                data.unavailable.push(source.key());
+            }
+        }
+    }
+    data
+}
+
+pub struct WeatherSource;
+
+impl DataSource for WeatherSource {
+    fn key(&self) -> Source {
+        Source::Weather
+    }
+
+    fn fetch<'a>(
+        &'a self,
+        context: &'a FetchContext<'a>,
+    ) -> BoxFuture<'a, anyhow::Result<Fetched>> {
+        Box::pin(async move {
+            let cached = fetch_weather(context, context.coords).await?;
+            Ok(Fetched::Weather(match context.config.timezone {
+                Some(timezone) => CachedWeather {
+                    data: cached.data.with_timezone(timezone),
+                    ..cached
+                },
+                None => cached,
+            }))
+        })
+    }
+}
+
+pub struct LocationsSource;
+
+impl DataSource for LocationsSource {
+    fn key(&self) -> Source {
+        Source::Locations
+    }
+
+    fn fetch<'a>(
+        &'a self,
+        context: &'a FetchContext<'a>,
+    ) -> BoxFuture<'a, anyhow::Result<Fetched>> {
+        Box::pin(async move {
+            let secondary = context.secondary;
+            let weather = join_all(
+                secondary
+                    .iter()
+                    .map(|place| fetch_weather(context, place.coordinates())),
+            )
+            .await;
+            Ok(Fetched::Locations(
+                secondary
+                    .iter()
+                    .zip(weather)
+                    .map(|(place, weather)| SecondaryLocation {
+                        name: place.name.clone(),
+                        weather: weather
+                            .inspect_err(|err| {
+                                error!(?err, location = %place.name, "failed to fetch weather; showing placeholders");
+                            })
+                            .ok(),
+                    })
+                    .collect(),
+            ))
+        })
+    }
+}
+
+/// Weather for `coords` through the cache. The refresh runs in its own task
+/// so one that misses the deadline still lands in the cache for the next
+/// render. Meanwhile the last good response stands in for it.
+async fn fetch_weather(
+    context: &FetchContext<'_>,
+    coords: Coordinates,
+) -> anyhow::Result<CachedWeather> {
+    let config = context.config;
+    let cache = context.state.cache.clone();
+    let provider: Arc<dyn WeatherProvider> =
+        context.state.providers.get(config.provider, config).into();
+    let query = WeatherQuery {
+        units: config.units,
+        forecast_days: config.forecast_days(),
+    };
+    let ttl = config.cache_ttl;
+
+    let refresh = tokio::spawn({
+        let (cache, provider) = (cache.clone(), provider.clone());
+        async move { cache.fetch(provider.as_ref(), coords, query, ttl).await }
+    });
+    let wait = async { refresh.await.context("weather refresh panicked")? };
+    context.within(wait).await.or_else(|err| {
+        let cached = cache.last(provider.as_ref(), coords, query).ok_or(err)?;
+        warn!("weather fetch timed out; serving stale cache entry");
+        Ok(cached)
+    })
+}
+
+pub struct HistorySource;
+
+impl DataSource for HistorySource {
+    fn key(&self) -> Source {
+        Source::History
+    }
+
+    /// The comparison is an extra line, so a missing one doesn't tag the
+    /// weather widget.
+    fn is_optional(&self) -> bool {
+        true
+    }
+
+    /// Cached for the day (the normal for the month), so this only waits on
+    /// the first render of each. It needs the location's timezone to know which day is yesterday, which
+    /// is looked up on its own rather than waiting for the weather.
+    fn fetch<'a>(
+        &'a self,
+        context: &'a FetchContext<'a>,
+    ) -> BoxFuture<'a, anyhow::Result<Fetched>> {
+        Box::pin(async move {
+            let (state, config, coords) = (context.state, context.config, context.coords);
+            let fetch = async {
+                let timezone = state.providers.timezone(config.timezone, coords).await;
+                let today = Utc::now().with_timezone(&timezone).date_naive();
+                state
+                    .history
+                    .fetch(coords, config.units, timezone, today)
+                    .await
+            };
+            Ok(Fetched::History(context.within(fetch).await?))
+        })
+    }
+}
+
+pub struct AirQualitySource;
+
+impl DataSource for AirQualitySource {
+    fn key(&self) -> Source {
+        Source::AirQuality
+    }
+
+    fn fetch<'a>(
+        &'a self,
+        context: &'a FetchContext<'a>,
+    ) -> BoxFuture<'a, anyhow::Result<Fetched>> {
+        Box::pin(async move {
+            let client = context.state.air_quality.clone();
+            let coords = context.coords;
+            // Like the weather, a refresh that misses the deadline still
+            // lands in the cache for the next render
+            let refresh = tokio::spawn({
+                let client = client.clone();
+                async move { client.fetch(coords).await }
+            });
+            let wait = async { refresh.await.context("air quality refresh panicked")? };
+            let air_quality = context.within(wait).await.or_else(|err| {
+                let last = client.last(coords).ok_or(err)?;
+                warn!("air quality fetch timed out; serving the last readings");
+                anyhow::Ok(last)
+            })?;
+            Ok(Fetched::AirQuality(air_quality))
+        })
+    }
+}
+
+pub struct KanjiSource;
+
+impl DataSource for KanjiSource {
+    fn key(&self) -> Source {
+        Source::Kanji
+    }
+
+    fn fetch<'a>(
+        &'a self,
+        context: &'a FetchContext<'a>,
+    ) -> BoxFuture<'a, anyhow::Result<Fetched>> {
+        Box::pin(async move {
+            let fetch = context
+                .state
+                .wanikani
+                .fetch_pending_kanji(context.config.wanikani_token.as_deref(), KANJI_CARDS);
+            Ok(Fetched::Kanji(context.within(fetch).await?))
+        })
+    }
+}
+
+/// The `[alerts]` source, fetched for the banner whenever it is configured.
+pub struct AlertsSource;
+
+impl DataSource for AlertsSource {
+    fn key(&self) -> Source {
+        Source::Alerts
+    }
+
+    /// A failed fetch hides the banner.
+    fn is_optional(&self) -> bool {
+        true
+    }
+
+    fn fetch<'a>(
+        &'a self,
+        context: &'a FetchContext<'a>,
+    ) -> BoxFuture<'a, anyhow::Result<Fetched>> {
+        Box::pin(async move {
+            let settings = context
+                .config
+                .alerts
+                .as_ref()
+                .context("alerts aren't configured")?;
+            let source = context.state.alerts.get(settings);
+            let alerts = context
+                .within(source.fetch(context.coords))
+                .await
+                .with_context(|| format!("failed to fetch {:?} alerts", source.kind()))?;
+            Ok(Fetched::Alerts(alerts))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use std::sync::RwLock;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::air_quality::AirQualityClient;
+    use crate::alerts::AlertSources;
+    use crate::cache::WeatherCache;
+    use crate::geocoding::Geocoders;
+    use crate::history::HistoryClient;
+    use crate::prerender::Prerendered;
+    use crate::provider::WeatherProviders;
+    use crate::wanikani::WanikaniClient;
+
+    /// Counts its fetches, and fails them unless `succeeds`.
+    struct Counting {
+        key: Source,
+        optional: bool,
+        succeeds: bool,
+        calls: AtomicUsize,
+    }
+
+    impl Counting {
+        const fn new(key: Source, optional: bool, succeeds: bool) -> Self {
+            Self {
+                key,
+                optional,
+                succeeds,
+                calls: AtomicUsize::new(0),
+            }
+        }
+    }
+
+    impl DataSource for Counting {
+        fn key(&self) -> Source {
+            self.key
+        }
+
+        fn is_optional(&self) -> bool {
+            self.optional
+        }
+
+        fn fetch<'a>(
+            &'a self,
+            _context: &'a FetchContext<'a>,
+        ) -> BoxFuture<'a, anyhow::Result<Fetched>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async move {
+                if self.succeeds {
+                    Ok(Fetched::Kanji(Vec::new()))
+                } else {
+                    Err(anyhow!("upstream is down"))
+                }
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn fetches_each_key_once_and_tags_failures() {
+        static KANJI: Counting = Counting::new(Source::Kanji, false, true);
+        static KANJI_AGAIN: Counting = Counting::new(Source::Kanji, false, true);
+        static HISTORY: Counting = Counting::new(Source::History, true, false);
+        static AIR_QUALITY: Counting = Counting::new(Source::AirQuality, false, false);
+
+        let path = Path::new(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/dashboard.example.toml"
+        ));
+        let config = DashboardConfig::load(path).unwrap();
+        let state = AppState {
+            providers: WeatherProviders::new(),
+            cache: Arc::new(WeatherCache::new()),
+            config_path: path.to_path_buf(),
+            config: RwLock::new(Arc::new(config.clone())),
+            wanikani: WanikaniClient::new(),
+            air_quality: AirQualityClient::new(),
+            alerts: AlertSources::new(),
+            history: HistoryClient::new(),
+            geocoders: Geocoders::new(),
+            prerendered: Prerendered::new(),
+        };
+        let coords = Coordinates {
+            latitude: config.latitude,
+            longitude: config.longitude,
+        };
+        let context = FetchContext::new(&state, &config, coords, &[]);
+
+        let data = fetch_all(&context, &[&KANJI, &HISTORY, &KANJI_AGAIN, &AIR_QUALITY]).await;
+
+        assert_eq!(KANJI.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(KANJI_AGAIN.calls.load(Ordering::SeqCst), 0);
+        assert_eq!(HISTORY.calls.load(Ordering::SeqCst), 1);
+        // the optional history is left out without tagging anything
+        assert_eq!(data.unavailable, [Source::AirQuality]);
+        assert!(data.comparison.is_none());
+    }
+}
@@ -0,0 +1,114 @@
+use serde::Deserialize;
+
+/// Measurement system for values requested from the weather API and shown on
+/// the dashboard. `Mixed` is the UK convention: Celsius with miles per hour.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Units {
+    #[default]
+    Metric,
+    Imperial,
+    Mixed,
+}
+
+impl Units {
+    /// Open-Meteo `temperature_unit` query value.
+    pub fn temperature_param(self) -> &'static str {
+        match self {
+            Units::Metric | Units::Mixed => "celsius",
+            Units::Imperial => "fahrenheit",
+        }
+    }
+
+    /// Open-Meteo `wind_speed_unit` query value.
+    pub fn wind_speed_param(self) -> &'static str {
+        match self {
+            Units::Metric => "kmh",
+            Units::Imperial | Units::Mixed => "mph",
+        }
+    }
+
+    /// Open-Meteo `precipitation_unit` query value.
+    pub fn precipitation_param(self) -> &'static str {
+        match self {
+            Units::Metric | Units::Mixed => "mm",
+            Units::Imperial => "inch",
+        }
+    }
+
+    /// Converts a temperature from providers that only report Celsius.
+    pub fn temperature_from_celsius(self, celsius: f64) -> f64 {
+        match self {
+            Units::Metric | Units::Mixed => celsius,
+            Units::Imperial => celsius * 9.0 / 5.0 + 32.0,
+        }
+    }
+
+    /// Converts a precipitation amount from providers that only report mm.
+    pub fn precipitation_from_mm(self, mm: f64) -> f64 {
+        match self {
+            Units::Metric | Units::Mixed => mm,
+            Units::Imperial => mm / 25.4,
+        }
+    }
+
+    /// Converts a wind speed from providers that only report m/s.
+    pub fn wind_speed_from_ms(self, ms: f64) -> f64 {
+        match self {
+            Units::Metric => ms * 3.6,
+            Units::Imperial | Units::Mixed => ms * 2.236_936,
+        }
+    }
+
+    /// Pressure is always fetched in hPa; only imperial shows inches of
+    /// mercury.
+    pub fn pressure_from_hpa(self, hpa: f64) -> f64 {
+        match self {
+            Units::Metric | Units::Mixed => hpa,
+            Units::Imperial => hpa * 0.029_53,
+        }
+    }
+
+    /// Visibility is always fetched in metres; the UK reports it in miles.
+    pub fn visibility_from_m(self, metres: f64) -> f64 {
+        match self {
+            Units::Metric => metres / 1000.0,
+            Units::Imperial | Units::Mixed => metres / 1609.344,
+        }
+    }
+
+    pub fn precipitation_suffix(self) -> &'static str {
+        match self {
+            Units::Metric | Units::Mixed => "mm",
+            Units::Imperial => "in",
+        }
+    }
+
+    pub fn wind_speed_suffix(self) -> &'static str {
+        match self {
+            Units::Metric => "km/h",
+            Units::Imperial | Units::Mixed => "mph",
+        }
+    }
+
+    pub fn pressure_suffix(self) -> &'static str {
+        match self {
+            Units::Metric | Units::Mixed => "hPa",
+            Units::Imperial => "inHg",
+        }
+    }
+
+    pub fn visibility_suffix(self) -> &'static str {
+        match self {
+            Units::Metric => "km",
+            Units::Imperial | Units::Mixed => "mi",
+        }
+    }
+
+    pub fn temperature_suffix(self) -> &'static str {
+        match self {
+            Units::Metric | Units::Mixed => "°C",
+            Units::Imperial => "°F",
+        }
+    }
+}
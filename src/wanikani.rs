@@ -5,24 +5,21 @@ use serde::Deserialize;
 #[derive(Clone, Debug)]
 pub struct WanikaniClient {
     http: Client,
-    token: Option<String>,
 }
 
 impl WanikaniClient {
     pub fn new() -> Self {
-        let token = std::env::var("WANIKANI_API_TOKEN").ok();
-
         Self {
             http: Client::new(),
-            token,
         }
     }
 
-    pub async fn fetch_pending_kanji(&self, limit: usize) -> anyhow::Result<Vec<WanikaniKanji>> {
-        let token = self
-            .token
-            .as_ref()
-            .context("WANIKANI_API_TOKEN is not set")?;
+    pub async fn fetch_pending_kanji(
+        &self,
+        token: Option<&str>,
+        limit: usize,
+    ) -> anyhow::Result<Vec<WanikaniKanji>> {
+        let token = token.context("WaniKani token is not configured")?;
 
         let mut assignments: Vec<Assignment> = Vec::new();
         let mut next_url = Some(
@@ -0,0 +1,75 @@
+//! Dashboard widgets. Each one names the data sources it is built from, so a
+//! render only fetches what the layout shows, and turns the fetched data into
+//! the `Dict` its Typst module's `render-<key>` function draws. Fetching lives
+//! in [`crate::source`], where widgets sharing a source share its fetch.
+
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use typst::foundations::Dict;
+
+use crate::config::{DashboardConfig, WidgetKind};
+use crate::source::DataSource;
+use crate::{Coordinates, DashboardData, RenderParams};
+
+mod air_quality;
+mod astronomy;
+mod chart;
+mod daily;
+mod locations;
+mod wanikani;
+mod weather;
+
+use air_quality::AirQualityWidget;
+use astronomy::AstronomyWidget;
+use chart::ChartWidget;
+use daily::DailyWidget;
+use locations::LocationsWidget;
+use wanikani::WanikaniWidget;
+use weather::WeatherWidget;
+
+pub use wanikani::KANJI_CARDS;
+
+/// What a widget is built from besides the fetched data.
+pub struct WidgetContext<'a> {
+    pub config: &'a DashboardConfig,
+    pub coords: Coordinates,
+    /// Place name from the geocoder or a location set.
+    pub name: Option<&'a str>,
+    pub params: &'a RenderParams,
+    /// Zone dates and times are shown in: the config's, else the weather
+    /// provider's, else the nautical zone for the longitude.
+    pub timezone: Tz,
+    pub now: DateTime<Utc>,
+}
+
+pub trait Widget: Sync {
+    /// Typst module defining `render-<key>`, relative to `templates/`.
+    fn template(&self) -> &'static str;
+
+    /// Data to fetch when the widget is on the dashboard.
+    fn sources(&self, config: &DashboardConfig) -> Vec<&'static dyn DataSource>;
+
+    /// Inputs for the template. Sources that failed are `None` or empty in
+    /// `data`, and the widget fills in placeholders.
+    fn build(&self, context: &WidgetContext<'_>, data: &DashboardData) -> Dict;
+
+    /// Whether the widget is drawn without data it needed, and gets tagged
+    /// as such.
+    fn is_unavailable(&self, config: &DashboardConfig, data: &DashboardData) -> bool {
+        self.sources(config)
+            .iter()
+            .any(|source| data.unavailable.contains(&source.key()))
+    }
+}
+
+pub fn get(kind: WidgetKind) -> &'static dyn Widget {
+    match kind {
+        WidgetKind::Weather => &WeatherWidget,
+        WidgetKind::Locations => &LocationsWidget,
+        WidgetKind::Daily => &DailyWidget,
+        WidgetKind::Chart => &ChartWidget,
+        WidgetKind::Astronomy => &AstronomyWidget,
+        WidgetKind::AirQuality => &AirQualityWidget,
+        WidgetKind::Wanikani => &WanikaniWidget,
+    }
+}
@@ -0,0 +1,99 @@
+use typst::foundations::{Array, Dict, IntoValue, Str, Value};
+
+use super::{Widget, WidgetContext};
+use crate::DashboardData;
+use crate::air_quality::{AirQuality, Reading};
+use crate::config::DashboardConfig;
+use crate::locale::{Locale, Text};
+use crate::source::{AirQualitySource, DataSource};
+
+/// Pollutant and pollen levels from the air quality API.
+pub struct AirQualityWidget;
+
+impl Widget for AirQualityWidget {
+    fn template(&self) -> &'static str {
+        "air-quality.typ"
+    }
+
+    fn sources(&self, _config: &DashboardConfig) -> Vec<&'static dyn DataSource> {
+        vec![&AirQualitySource]
+    }
+
+    fn build(&self, context: &WidgetContext<'_>, data: &DashboardData) -> Dict {
+        build_air_quality_data(data.air_quality.as_ref(), context.config.locale)
+    }
+}
+
+/// Pollutant and pollen tiles, each with its level on the reading's scale.
+/// Readings the API left empty are skipped, so outside Europe `pollen` is
+/// usually empty and the panel says so instead.
+fn build_air_quality_data(air_quality: Option<&AirQuality>, locale: Locale) -> Dict {
+    let tiles = |readings: &[Reading]| -> Vec<Value> {
+        let Some(air_quality) = air_quality else {
+            return Vec::new();
+        };
+        readings
+            .iter()
+            .filter_map(|&reading| {
+                let value = air_quality.value(reading)?;
+                let scale = reading.scale();
+                let level = reading.level(value);
+                let mut tile = Dict::new();
+                tile.insert(
+                    Str::from("label"),
+                    air_quality_label(reading, locale).into_value(),
+                );
+                tile.insert(
+                    Str::from("value"),
+                    format!("{:.0}", value.round()).into_value(),
+                );
+                if let Some(unit) = reading.unit() {
+                    tile.insert(Str::from("unit"), unit.into_value());
+                }
+                tile.insert(Str::from("level"), (level as i64).into_value());
+                tile.insert(Str::from("levels"), (scale.levels() as i64).into_value());
+                tile.insert(
+                    Str::from("level_name"),
+                    locale.air_quality_level(scale, level).into_value(),
+                );
+                Some(tile.into_value())
+            })
+            .collect()
+    };
+
+    let mut labels = Dict::new();
+    labels.insert(
+        Str::from("unavailable"),
+        locale.text(Text::AirQualityUnavailable).into_value(),
+    );
+    labels.insert(
+        Str::from("no_pollen"),
+        locale.text(Text::NoPollenData).into_value(),
+    );
+
+    let mut data = Dict::new();
+    data.insert(Str::from("available"), air_quality.is_some().into_value());
+    data.insert(
+        Str::from("pollutants"),
+        Array::from_iter(tiles(&Reading::POLLUTANTS)).into_value(),
+    );
+    data.insert(
+        Str::from("pollen"),
+        Array::from_iter(tiles(&Reading::POLLEN)).into_value(),
+    );
+    data.insert(Str::from("labels"), labels.into_value());
+    data
+}
+
+fn air_quality_label(reading: Reading, locale: Locale) -> &'static str {
+    match reading {
+        Reading::EuropeanAqi => locale.text(Text::EuropeanAqi),
+        Reading::UsAqi => locale.text(Text::UsAqi),
+        Reading::Pm2_5 => "PM2.5",
+        Reading::Pm10 => "PM10",
+        Reading::Ozone => locale.text(Text::Ozone),
+        Reading::Birch => locale.text(Text::Birch),
+        Reading::Grass => locale.text(Text::Grass),
+        Reading::Ragweed => locale.text(Text::Ragweed),
+    }
+}
@@ -0,0 +1,116 @@
+use chrono::{DateTime, Duration, Utc};
+use chrono_tz::Tz;
+use typst::foundations::{Dict, IntoValue, Str};
+
+use super::{Widget, WidgetContext};
+use crate::config::DashboardConfig;
+use crate::locale::{Locale, Text};
+use crate::source::{DataSource, WeatherSource};
+use crate::{Coordinates, DashboardData, astronomy};
+
+/// Sun and moon times, computed locally.
+pub struct AstronomyWidget;
+
+impl Widget for AstronomyWidget {
+    fn template(&self) -> &'static str {
+        "astronomy.typ"
+    }
+
+    /// Only needs the weather for the location's timezone, when the config
+    /// doesn't set one.
+    fn sources(&self, config: &DashboardConfig) -> Vec<&'static dyn DataSource> {
+        if config.timezone.is_some() {
+            Vec::new()
+        } else {
+            vec![&WeatherSource]
+        }
+    }
+
+    fn build(&self, context: &WidgetContext<'_>, _data: &DashboardData) -> Dict {
+        build_astronomy_data(
+            context.coords,
+            context.timezone,
+            context.config.locale,
+            context.now,
+        )
+    }
+
+    /// Falls back to the nautical timezone, so it is complete either way.
+    fn is_unavailable(&self, _config: &DashboardConfig, _data: &DashboardData) -> bool {
+        false
+    }
+}
+
+/// Today's sun and moon times at `coords`, computed without the weather API.
+fn build_astronomy_data(
+    coords: Coordinates,
+    timezone: Tz,
+    locale: Locale,
+    now: DateTime<Utc>,
+) -> Dict {
+    let today = now.with_timezone(&timezone).date_naive();
+    let sun = astronomy::sun_times(coords, today, timezone);
+    let yesterday = astronomy::sun_times(coords, today - Duration::days(1), timezone);
+    let moon = astronomy::moon_times(coords, today, timezone);
+    let phase = astronomy::moon_phase(now);
+    let format_time = |ts: Option<DateTime<Tz>>| {
+        ts.map(|ts| locale.format_time(&ts))
+            .unwrap_or_else(|| "--".to_string())
+    };
+
+    let mut moon_data = Dict::new();
+    moon_data.insert(Str::from("phase"), phase.phase.into_value());
+    moon_data.insert(Str::from("index"), (phase.index() as i64).into_value());
+    moon_data.insert(
+        Str::from("name"),
+        locale.moon_phase(phase.index()).into_value(),
+    );
+    moon_data.insert(
+        Str::from("illumination"),
+        format!("{:.0}%", (phase.illumination * 100.0).round()).into_value(),
+    );
+    moon_data.insert(
+        Str::from("moonrise"),
+        format_time(moon.moonrise).into_value(),
+    );
+    moon_data.insert(Str::from("moonset"), format_time(moon.moonset).into_value());
+
+    let mut labels = Dict::new();
+    for (key, text) in [
+        ("sunrise", Text::Sunrise),
+        ("sunset", Text::Sunset),
+        ("daylight", Text::Daylight),
+        ("twilight", Text::CivilTwilight),
+        ("moonrise", Text::Moonrise),
+        ("moonset", Text::Moonset),
+    ] {
+        labels.insert(Str::from(key), locale.text(text).into_value());
+    }
+
+    let mut data = Dict::new();
+    data.insert(Str::from("sunrise"), format_time(sun.sunrise).into_value());
+    data.insert(Str::from("sunset"), format_time(sun.sunset).into_value());
+    data.insert(
+        Str::from("civil_dawn"),
+        format_time(sun.civil_dawn).into_value(),
+    );
+    data.insert(
+        Str::from("civil_dusk"),
+        format_time(sun.civil_dusk).into_value(),
+    );
+    data.insert(
+        Str::from("daylight"),
+        locale.format_day_length(sun.day_length()).into_value(),
+    );
+    data.insert(
+        Str::from("daylight_change"),
+        locale
+            .format_day_length_change(sun.day_length() - yesterday.day_length())
+            .into_value(),
+    );
+    data.insert(Str::from("moon"), moon_data.into_value());
+    // The lit side of the moon is mirrored south of the equator
+    data.insert(Str::from("southern"), (coords.latitude < 0.0).into_value());
+    data.insert(Str::from("labels"), labels.into_value());
+    data
+}
@@ -0,0 +1,168 @@
+use chrono::{DateTime, Duration, Timelike, Utc};
+use typst::foundations::{Array, Dict, IntoValue, Str};
+
+use super::{Widget, WidgetContext};
+use crate::config::DashboardConfig;
+use crate::locale::Text;
+use crate::source::{DataSource, WeatherSource};
+use crate::{DashboardData, HourlyForecast};
+
+/// Temperature line and precipitation bars for the next `chart_hours`.
+pub struct ChartWidget;
+
+impl Widget for ChartWidget {
+    fn template(&self) -> &'static str {
+        "chart.typ"
+    }
+
+    fn sources(&self, _config: &DashboardConfig) -> Vec<&'static dyn DataSource> {
+        vec![&WeatherSource]
+    }
+
+    fn build(&self, context: &WidgetContext<'_>, data: &DashboardData) -> Dict {
+        let hourly = data
+            .weather
+            .as_ref()
+            .map(|cached| cached.data.hourly.as_slice())
+            .unwrap_or_default();
+        build_chart_data(hourly, context.config, context.now)
+    }
+}
+
+/// Numeric series for the temperature/precipitation chart. `x` values are
+/// hours since the first plotted point, the last one before `now`.
+fn build_chart_data(
+    hourly: &[HourlyForecast],
+    config: &DashboardConfig,
+    now: DateTime<Utc>,
+) -> Dict {
+    let locale = config.locale;
+    let end = now + Duration::hours(config.chart_hours as i64);
+    let start = hourly
+        .iter()
+        .rev()
+        .find(|period| period.time <= now)
+        .map(|period| period.time.with_timezone(&Utc))
+        .unwrap_or(now);
+    let hours_since_start =
+        |ts: DateTime<Utc>| ts.signed_duration_since(start).num_minutes() as f64 / 60.0;
+
+    let series: Vec<&HourlyForecast> = hourly
+        .iter()
+        .filter(|period| period.time >= start && period.time <= end)
+        .collect();
+
+    let points = series.iter().map(|period| {
+        let mut point = Dict::new();
+        point.insert(
+            Str::from("x"),
+            hours_since_start(period.time.with_timezone(&Utc)).into_value(),
+        );
+        point.insert(Str::from("temperature"), period.temperature.into_value());
+        point.insert(
+            Str::from("precipitation"),
+            period.precipitation_probability.into_value(),
+        );
+        point.into_value()
+    });
+
+    // Label every six clock hours so the axis reads 00, 06, 12, 18
+    let ticks = series
+        .iter()
+        .filter(|period| period.time.minute() == 0 && period.time.hour() % 6 == 0)
+        .map(|period| {
+            let mut tick = Dict::new();
+            tick.insert(
+                Str::from("x"),
+                hours_since_start(period.time.with_timezone(&Utc)).into_value(),
+            );
+            tick.insert(
+                Str::from("label"),
+                locale.format_hour(&period.time).into_value(),
+            );
+            tick.into_value()
+        });
+
+    let (min, max) = series
+        .iter()
+        .map(|period| period.temperature)
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), t| {
+            (min.min(t), max.max(t))
+        });
+    let mut axis = Dict::new();
+    if min <= max {
+        let step = [1, 2, 5, 10, 20]
+            .into_iter()
+            .find(|step| (max - min) / *step as f64 <= 4.0)
+            .unwrap_or(50);
+        let axis_min = (min / step as f64).floor() as i64 * step;
+        let mut axis_max = (max / step as f64).ceil() as i64 * step;
+        if axis_max == axis_min {
+            axis_max += step;
+        }
+        axis.insert(Str::from("min"), axis_min.into_value());
+        axis.insert(Str::from("max"), axis_max.into_value());
+        axis.insert(Str::from("step"), step.into_value());
+    }
+
+    let mut labels = Dict::new();
+    labels.insert(
+        Str::from("temperature"),
+        config.units.temperature_suffix().into_value(),
+    );
+    labels.insert(Str::from("precipitation"), "%".into_value());
+    labels.insert(Str::from("now"), locale.text(Text::Now).into_value());
+    labels.insert(
+        Str::from("unavailable"),
+        locale.text(Text::WeatherUnavailable).into_value(),
+    );
+
+    let mut chart = Dict::new();
+    chart.insert(Str::from("points"), Array::from_iter(points).into_value());
+    chart.insert(Str::from("ticks"), Array::from_iter(ticks).into_value());
+    chart.insert(Str::from("span"), hours_since_start(end).into_value());
+    chart.insert(
+        Str::from("now"),
+        hours_since_start(now_marker(&series, now)).into_value(),
+    );
+    chart.insert(Str::from("temperature_axis"), axis.into_value());
+    chart.insert(Str::from("labels"), labels.into_value());
+    chart
+}
+
+/// Where the "now" marker goes: on the data point closest to `now`, so the
+/// image (and its ETag) only changes once per data interval rather than
+/// every minute.
+fn now_marker(series: &[&HourlyForecast], now: DateTime<Utc>) -> DateTime<Utc> {
+    series
+        .iter()
+        .map(|period| period.time.with_timezone(&Utc))
+        .min_by_key(|time| time.signed_duration_since(now).num_seconds().abs())
+        .unwrap_or(now)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn now_marker_snaps_to_the_nearest_data_point() {
+        let first = Utc.with_ymd_and_hms(2026, 10, 17, 9, 0, 0).unwrap();
+        let hourly: Vec<HourlyForecast> = (0..3)
+            .map(|index| HourlyForecast {
+                time: (first + Duration::hours(index)).with_timezone(&chrono_tz::UTC),
+                temperature: 10.0,
+                precipitation_probability: None,
+            })
+            .collect();
+        let series: Vec<&HourlyForecast> = hourly.iter().collect();
+        let at = |hour, minute| Utc.with_ymd_and_hms(2026, 10, 17, hour, minute, 0).unwrap();
+
+        assert_eq!(now_marker(&series, at(9, 1)), at(9, 0));
+        assert_eq!(now_marker(&series, at(9, 29)), at(9, 0));
+        assert_eq!(now_marker(&series, at(9, 31)), at(10, 0));
+        assert_eq!(now_marker(&[], at(9, 31)), at(9, 31));
+    }
+}
@@ -0,0 +1,98 @@
+use chrono::{DateTime, Datelike};
+use chrono_tz::Tz;
+use typst::foundations::{Array, Dict, IntoValue, Str, Value};
+
+use super::{Widget, WidgetContext};
+use crate::config::DashboardConfig;
+use crate::locale::Text;
+use crate::source::{DataSource, WeatherSource};
+use crate::{DashboardData, astronomy};
+
+/// One card per day for the next `daily_days` days.
+pub struct DailyWidget;
+
+impl Widget for DailyWidget {
+    fn template(&self) -> &'static str {
+        "weather.typ"
+    }
+
+    fn sources(&self, _config: &DashboardConfig) -> Vec<&'static dyn DataSource> {
+        vec![&WeatherSource]
+    }
+
+    fn build(&self, context: &WidgetContext<'_>, data: &DashboardData) -> Dict {
+        let config = context.config;
+        let locale = config.locale;
+        let degrees = config.units.temperature_suffix();
+        let days: Vec<Value> = data
+            .weather
+            .as_ref()
+            .map(|cached| cached.data.daily.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .take(config.daily_days as usize)
+            .enumerate()
+            .map(|(index, day)| {
+                let precipitation = config.units.precipitation_suffix();
+                let format_time = |ts: Option<DateTime<Tz>>| {
+                    ts.map(|ts| locale.format_time(&ts))
+                        .unwrap_or_else(|| "--".to_string())
+                };
+
+                let mut card = Dict::new();
+                let name = if index == 0 {
+                    locale.text(Text::Today)
+                } else {
+                    locale.short_day_name(day.date.weekday())
+                };
+                card.insert(Str::from("day"), name.into_value());
+                card.insert(
+                    Str::from("condition"),
+                    locale.weather_description(day.condition).into_value(),
+                );
+                card.insert(Str::from("icon"), day.condition.icon_key(true).into_value());
+                card.insert(
+                    Str::from("high"),
+                    format!("{:.0}{degrees}", day.temperature_max.round()).into_value(),
+                );
+                card.insert(
+                    Str::from("low"),
+                    format!("{:.0}{degrees}", day.temperature_min.round()).into_value(),
+                );
+                card.insert(
+                    Str::from("precipitation"),
+                    day.precipitation_sum
+                        .map(|sum| format!("{sum:.1} {precipitation}"))
+                        .unwrap_or_else(|| "--".to_string())
+                        .into_value(),
+                );
+                card.insert(
+                    Str::from("rain"),
+                    day.precipitation_probability
+                        .map(|p| format!("{:.0}%", p.round()))
+                        .unwrap_or_else(|| "--".to_string())
+                        .into_value(),
+                );
+                // Providers without a daily endpoint report no sun times
+                let (sunrise, sunset) = match (day.sunrise, day.sunset) {
+                    (Some(sunrise), Some(sunset)) => (Some(sunrise), Some(sunset)),
+                    _ => {
+                        let sun = astronomy::sun_times(context.coords, day.date, context.timezone);
+                        (sun.sunrise, sun.sunset)
+                    }
+                };
+                card.insert(Str::from("sunrise"), format_time(sunrise).into_value());
+                card.insert(Str::from("sunset"), format_time(sunset).into_value());
+                card.into_value()
+            })
+            .collect();
+
+        let mut daily_data = Dict::new();
+        daily_data.insert(Str::from("days"), Array::from_iter(days).into_value());
+        daily_data.insert(
+            Str::from("unavailable"),
+            locale.text(Text::WeatherUnavailable).into_value(),
+        );
+        daily_data
+    }
+}
@@ -0,0 +1,76 @@
+use chrono::{DateTime, Utc};
+use typst::foundations::{Array, Dict, IntoValue, Str};
+
+use super::{Widget, WidgetContext};
+use crate::condition::WeatherCondition;
+use crate::config::DashboardConfig;
+use crate::source::{DataSource, LocationsSource};
+use crate::{DashboardData, SecondaryLocation};
+
+/// Compact cards for the secondary places of a location set.
+pub struct LocationsWidget;
+
+impl Widget for LocationsWidget {
+    fn template(&self) -> &'static str {
+        "weather.typ"
+    }
+
+    fn sources(&self, _config: &DashboardConfig) -> Vec<&'static dyn DataSource> {
+        vec![&LocationsSource]
+    }
+
+    fn build(&self, context: &WidgetContext<'_>, data: &DashboardData) -> Dict {
+        build_locations_data(&data.locations, context.config, context.now)
+    }
+}
+
+/// Compact cards for the secondary locations of a location set. Each uses
+/// its own timezone to pick today's high and low.
+fn build_locations_data(
+    locations: &[SecondaryLocation],
+    config: &DashboardConfig,
+    now: DateTime<Utc>,
+) -> Dict {
+    let degrees = config.units.temperature_suffix();
+    let format_degrees = |value: Option<f64>| {
+        value
+            .map(|value| format!("{:.0}{degrees}", value.round()))
+            .unwrap_or_else(|| "--".to_string())
+    };
+
+    let cards = locations.iter().map(|location| {
+        let data = location.weather.as_ref().map(|cached| &cached.data);
+        let snapshot = data.map(|data| &data.snapshot);
+        let today = data.and_then(|data| {
+            let date = now.with_timezone(&data.timezone).date_naive();
+            data.daily.iter().find(|day| day.date >= date)
+        });
+        let condition = snapshot.map_or(WeatherCondition::Unknown, |s| s.condition);
+
+        let mut card = Dict::new();
+        card.insert(Str::from("name"), location.name.as_str().into_value());
+        card.insert(
+            Str::from("icon"),
+            condition
+                .icon_key(snapshot.is_none_or(|s| s.is_day))
+                .into_value(),
+        );
+        card.insert(
+            Str::from("temperature"),
+            format_degrees(snapshot.map(|s| s.temperature)).into_value(),
+        );
+        card.insert(
+            Str::from("high"),
+            format_degrees(today.map(|day| day.temperature_max)).into_value(),
+        );
+        card.insert(
+            Str::from("low"),
+            format_degrees(today.map(|day| day.temperature_min)).into_value(),
+        );
+        card.into_value()
+    });
+
+    let mut locations_data = Dict::new();
+    locations_data.insert(Str::from("locations"), Array::from_iter(cards).into_value());
+    locations_data
+}
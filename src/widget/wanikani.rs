@@ -0,0 +1,56 @@
+use typst::foundations::{Array, Dict, IntoValue, Str, Value};
+
+use super::{Widget, WidgetContext};
+use crate::DashboardData;
+use crate::config::DashboardConfig;
+use crate::locale::Text;
+use crate::source::{DataSource, KanjiSource};
+
+/// Kanji cards the template lays out in two rows of three.
+pub const KANJI_CARDS: usize = 6;
+
+/// Kanji with pending WaniKani reviews.
+pub struct WanikaniWidget;
+
+impl Widget for WanikaniWidget {
+    fn template(&self) -> &'static str {
+        "wanikani.typ"
+    }
+
+    fn sources(&self, _config: &DashboardConfig) -> Vec<&'static dyn DataSource> {
+        vec![&KanjiSource]
+    }
+
+    fn build(&self, context: &WidgetContext<'_>, data: &DashboardData) -> Dict {
+        let mut entries: Vec<Value> = data
+            .kanji
+            .iter()
+            .take(KANJI_CARDS)
+            .map(|item| {
+                let mut entry = Dict::new();
+                entry.insert(Str::from("kanji"), item.character.clone().into_value());
+                entry.insert(Str::from("meaning"), item.meaning.clone().into_value());
+                entry.into_value()
+            })
+            .collect();
+
+        // Pad to a full grid if needed
+        while entries.len() < KANJI_CARDS {
+            let mut entry = Dict::new();
+            entry.insert(Str::from("kanji"), "--".into_value());
+            entry.insert(
+                Str::from("meaning"),
+                context
+                    .config
+                    .locale
+                    .text(Text::NoPendingReviews)
+                    .into_value(),
+            );
+            entries.push(entry.into_value());
+        }
+
+        let mut wanikani_data = Dict::new();
+        wanikani_data.insert(Str::from("entries"), Array::from_iter(entries).into_value());
+        wanikani_data
+    }
+}
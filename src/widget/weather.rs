@@ -0,0 +1,459 @@
+use std::cmp::Ordering;
+
+use chrono::{DateTime, Datelike, Duration, Timelike, Utc};
+use typst::foundations::{Array, Dict, IntoValue, Str, Value};
+
+use super::{Widget, WidgetContext};
+use crate::condition::WeatherCondition;
+use crate::config::{DashboardConfig, HourlySampling, Metric};
+use crate::history::Comparison;
+use crate::locale::Text;
+use crate::nowcast::Nowcast;
+use crate::source::{DataSource, HistorySource, WeatherSource};
+use crate::units::Units;
+use crate::{CurrentMetrics, DashboardData, HourlyForecast};
+
+/// Pressure changes smaller than this over three hours count as steady.
+const PRESSURE_STEADY_HPA: f64 = 1.0;
+/// Days within this many °C of the climate normal count as near normal.
+const NEAR_NORMAL_C: f64 = 1.5;
+
+/// Current conditions, metric chips, the nowcast and the next few hours.
+pub struct WeatherWidget;
+
+impl Widget for WeatherWidget {
+    fn template(&self) -> &'static str {
+        "weather.typ"
+    }
+
+    fn sources(&self, config: &DashboardConfig) -> Vec<&'static dyn DataSource> {
+        if config.compare_history {
+            vec![&WeatherSource, &HistorySource]
+        } else {
+            vec![&WeatherSource]
+        }
+    }
+
+    fn build(&self, context: &WidgetContext<'_>, data: &DashboardData) -> Dict {
+        let config = context.config;
+        let params = context.params;
+        let now = context.now;
+        let weather = data.weather.as_ref();
+        let locale = config.locale;
+        let degrees = config.units.temperature_suffix();
+        let snapshot = weather.map(|cached| &cached.data.snapshot);
+        let weather_condition = snapshot.map_or(WeatherCondition::Unknown, |s| s.condition);
+        let icon = weather_condition.icon_key(snapshot.is_none_or(|s| s.is_day));
+        let condition = locale.weather_description(weather_condition);
+        let temperature = snapshot
+            .map(|s| format!("{:.0}{degrees}", s.temperature.round()))
+            .unwrap_or_else(|| "--".to_string());
+        let feels_like = snapshot
+            .map(|s| format!("{:.0}{degrees}", s.feels_like.round()))
+            .unwrap_or_else(|| "--".to_string());
+        let humidity = snapshot
+            .map(|s| format!("{:.0}%", s.humidity_pct.round()))
+            .unwrap_or_else(|| "--".to_string());
+        let observation_time = snapshot.and_then(|s| s.observation_time);
+        let day_label = observation_time
+            .map(|ts| locale.day_name(ts.weekday()))
+            .unwrap_or_else(|| locale.text(Text::Today));
+        let datetime_label = observation_time
+            .map(|ts| locale.format_datetime(&ts))
+            .unwrap_or_else(|| "--".to_string());
+
+        let battery = match params.battery_level {
+            Some(level) if params.is_charging.unwrap_or(false) => format!(
+                "{} {level}% ({})",
+                locale.text(Text::Battery),
+                locale.text(Text::Charging)
+            ),
+            Some(level) => format!("{} {level}%", locale.text(Text::Battery)),
+            None => locale.text(Text::BatteryUnavailable).to_string(),
+        };
+
+        let updated = match weather {
+            Some(cached) if cached.stale => format!(
+                "{} {}, {}",
+                locale.text(Text::Updated),
+                locale.format_age(now.signed_duration_since(cached.fetched_at)),
+                locale.text(Text::Offline)
+            ),
+            Some(cached) => format!(
+                "{} {}",
+                locale.text(Text::Updated),
+                cached
+                    .data
+                    .snapshot
+                    .observation_time
+                    .map(|ts| locale.format_datetime(&ts))
+                    .unwrap_or_else(|| "--".to_string())
+            ),
+            None => locale.text(Text::WeatherUnavailable).to_string(),
+        };
+
+        // Build hourly forecast array
+        let hourly = weather
+            .map(|cached| cached.data.hourly.as_slice())
+            .unwrap_or_default();
+        let mut hourly_cards: Vec<Value> = sample_hourly_forecast(hourly, config.hourly, now)
+            .into_iter()
+            .map(|period| {
+                let mut card = Dict::new();
+                card.insert(
+                    Str::from("time"),
+                    locale.format_time(&period.time).into_value(),
+                );
+                card.insert(
+                    Str::from("temperature"),
+                    format!("{:.0}{degrees}", period.temperature.round()).into_value(),
+                );
+                card.insert(
+                    Str::from("rain"),
+                    period
+                        .precipitation_probability
+                        .map(|p| format!("{:.0}%", p.round()))
+                        .unwrap_or_else(|| "--".to_string())
+                        .into_value(),
+                );
+                card.into_value()
+            })
+            .collect();
+
+        // Pad to the configured number of cards if needed
+        while hourly_cards.len() < config.hourly.count {
+            let mut card = Dict::new();
+            card.insert(Str::from("time"), "--".into_value());
+            card.insert(Str::from("temperature"), "--".into_value());
+            card.insert(Str::from("rain"), "--".into_value());
+            hourly_cards.push(card.into_value());
+        }
+
+        let mut weather_data = Dict::new();
+        weather_data.insert(Str::from("day"), day_label.into_value());
+        weather_data.insert(Str::from("datetime"), datetime_label.into_value());
+        weather_data.insert(Str::from("condition"), condition.into_value());
+        weather_data.insert(Str::from("icon"), icon.into_value());
+        weather_data.insert(Str::from("temperature"), temperature.into_value());
+        weather_data.insert(Str::from("real_feel"), feels_like.into_value());
+        weather_data.insert(Str::from("humidity"), humidity.into_value());
+        weather_data.insert(Str::from("battery"), battery.into_value());
+        weather_data.insert(Str::from("updated"), updated.into_value());
+        weather_data.insert(
+            Str::from("hours"),
+            Array::from_iter(hourly_cards).into_value(),
+        );
+        weather_data.insert(
+            Str::from("metrics"),
+            Array::from_iter(
+                snapshot
+                    .map(|s| build_metric_chips(&s.metrics, config))
+                    .unwrap_or_default(),
+            )
+            .into_value(),
+        );
+
+        if let Some(nowcast) =
+            weather.and_then(|cached| Nowcast::from_steps(&cached.data.minutely, now, config.units))
+        {
+            let mut strip = Dict::new();
+            strip.insert(
+                Str::from("summary"),
+                locale.nowcast_summary(&nowcast.outlook).into_value(),
+            );
+            strip.insert(
+                Str::from("bars"),
+                Array::from_iter(nowcast.bars.into_iter().map(IntoValue::into_value)).into_value(),
+            );
+            weather_data.insert(Str::from("nowcast"), strip.into_value());
+        }
+
+        if let Some(name) = context.name {
+            weather_data.insert(Str::from("location"), name.into_value());
+        }
+
+        if let Some(comparison) = data.comparison {
+            weather_data.insert(
+                Str::from("comparison"),
+                build_comparison(comparison, config).into_value(),
+            );
+        }
+
+        let mut labels = Dict::new();
+        labels.insert(
+            Str::from("humidity"),
+            locale.text(Text::Humidity).into_value(),
+        );
+        labels.insert(
+            Str::from("feels_like"),
+            locale.text(Text::FeelsLike).into_value(),
+        );
+        weather_data.insert(Str::from("labels"), labels.into_value());
+        weather_data
+    }
+}
+
+/// Picks the first entry in each upcoming clock slot of `interval_hours`
+/// (e.g. 12-15, 15-18), in whatever timezone `hourly` is expressed in. Hourly
+/// data lands exactly on 12, 15, 18; coarser steps that don't line up with
+/// the local clock (3-hourly UTC steps in UTC+1) take the next step instead.
+fn sample_hourly_forecast(
+    hourly: &[HourlyForecast],
+    sampling: HourlySampling,
+    now: DateTime<Utc>,
+) -> Vec<&HourlyForecast> {
+    let horizon = now + Duration::hours(sampling.horizon_hours as i64);
+    let mut last_slot = None;
+
+    hourly
+        .iter()
+        .filter(|period| period.time > now && period.time <= horizon)
+        .filter(|period| {
+            let local = period.time.naive_local();
+            let slot_hour = local.hour() / sampling.interval_hours * sampling.interval_hours;
+            let slot = local.date().and_hms_opt(slot_hour, 0, 0);
+            // A slot that already started would put an off-grid hour first
+            let upcoming = slot > Some(now.with_timezone(&period.time.timezone()).naive_local());
+            if !upcoming || slot == last_slot {
+                return false;
+            }
+            last_slot = slot;
+            true
+        })
+        .take(sampling.count)
+        .collect()
+}
+
+/// One chip per configured metric the provider reported, in config order.
+/// `icon` names the glyph; wind chips add the bearing the wind comes from and
+/// pressure chips the three hour trend.
+fn build_metric_chips(metrics: &CurrentMetrics, config: &DashboardConfig) -> Vec<Value> {
+    let units = config.units;
+    let locale = config.locale;
+    let degrees = units.temperature_suffix();
+
+    config
+        .metrics
+        .iter()
+        .filter_map(|metric| {
+            let mut chip = Dict::new();
+            let (label, value, unit) = match metric {
+                Metric::Wind => {
+                    let speed = metrics.wind_speed?;
+                    if let Some(direction) = metrics.wind_direction {
+                        chip.insert(
+                            Str::from("direction"),
+                            (direction.round() as i64).into_value(),
+                        );
+                        chip.insert(
+                            Str::from("detail"),
+                            locale.compass_point(direction).into_value(),
+                        );
+                    }
+                    (
+                        Text::Wind,
+                        format!("{:.0}", speed.round()),
+                        Some(units.wind_speed_suffix()),
+                    )
+                }
+                Metric::Gusts => (
+                    Text::Gusts,
+                    format!("{:.0}", metrics.wind_gusts?.round()),
+                    Some(units.wind_speed_suffix()),
+                ),
+                Metric::Pressure => {
+                    let pressure = units.pressure_from_hpa(metrics.pressure?);
+                    if let Some(change) = metrics.pressure_trend {
+                        let trend = if change >= PRESSURE_STEADY_HPA {
+                            "rising"
+                        } else if change <= -PRESSURE_STEADY_HPA {
+                            "falling"
+                        } else {
+                            "steady"
+                        };
+                        chip.insert(Str::from("trend"), trend.into_value());
+                    }
+                    let value = match units {
+                        Units::Imperial => format!("{pressure:.2}"),
+                        Units::Metric | Units::Mixed => format!("{:.0}", pressure.round()),
+                    };
+                    (Text::Pressure, value, Some(units.pressure_suffix()))
+                }
+                Metric::Uv => (
+                    Text::UvIndex,
+                    format!("{:.0}", metrics.uv_index?.round()),
+                    None,
+                ),
+                Metric::Visibility => {
+                    let distance = units.visibility_from_m(metrics.visibility?);
+                    let value = if distance < 10.0 {
+                        format!("{distance:.1}")
+                    } else {
+                        format!("{:.0}", distance.round())
+                    };
+                    (Text::Visibility, value, Some(units.visibility_suffix()))
+                }
+                Metric::DewPoint => (
+                    Text::DewPoint,
+                    format!("{:.0}{degrees}", metrics.dew_point?.round()),
+                    None,
+                ),
+                Metric::CloudCover => (
+                    Text::CloudCover,
+                    format!("{:.0}%", metrics.cloud_cover_pct?.round()),
+                    None,
+                ),
+            };
+            if let Some(unit) = unit {
+                chip.insert(Str::from("unit"), unit.into_value());
+            }
+            chip.insert(Str::from("icon"), metric.key().into_value());
+            chip.insert(Str::from("label"), locale.text(label).into_value());
+            chip.insert(Str::from("value"), value.into_value());
+            Some(chip.into_value())
+        })
+        .collect()
+}
+
+/// Lines for the weather widget: today against yesterday, then against the
+/// climate normal when the archive API answered.
+fn build_comparison(comparison: Comparison, config: &DashboardConfig) -> Array {
+    let locale = config.locale;
+    let mut lines = vec![
+        locale
+            .compare_yesterday(comparison.vs_yesterday.round() as i64)
+            .into_value(),
+    ];
+    if let Some(anomaly) = comparison.vs_normal {
+        let units = config.units;
+        let band =
+            units.temperature_from_celsius(NEAR_NORMAL_C) - units.temperature_from_celsius(0.0);
+        let ordering = if anomaly > band {
+            Ordering::Greater
+        } else if anomaly < -band {
+            Ordering::Less
+        } else {
+            Ordering::Equal
+        };
+        lines.push(
+            locale
+                .compare_normal(ordering, comparison.month)
+                .into_value(),
+        );
+    }
+    Array::from_iter(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use chrono_tz::Tz;
+
+    use super::*;
+
+    /// Forecast steps every `step_hours` from `first` (UTC), as a provider
+    /// would report them, in `timezone`.
+    fn steps(
+        first: DateTime<Utc>,
+        step_hours: i64,
+        count: i64,
+        timezone: Tz,
+    ) -> Vec<HourlyForecast> {
+        (0..count)
+            .map(|index| HourlyForecast {
+                time: (first + Duration::hours(index * step_hours)).with_timezone(&timezone),
+                temperature: 10.0,
+                precipitation_probability: None,
+            })
+            .collect()
+    }
+
+    fn sampled_hours(
+        hourly: &[HourlyForecast],
+        sampling: HourlySampling,
+        now: DateTime<Utc>,
+    ) -> Vec<u32> {
+        sample_hourly_forecast(hourly, sampling, now)
+            .iter()
+            .map(|period| period.time.hour())
+            .collect()
+    }
+
+    const EVERY_THREE_HOURS: HourlySampling = HourlySampling {
+        interval_hours: 3,
+        count: 4,
+        horizon_hours: 24,
+    };
+
+    #[test]
+    fn hourly_steps_land_on_clock_hours() {
+        let berlin = chrono_tz::Europe::Berlin;
+        let midnight = berlin.with_ymd_and_hms(2026, 1, 15, 0, 0, 0).unwrap();
+        let hourly = steps(midnight.with_timezone(&Utc), 1, 48, berlin);
+        let now = berlin.with_ymd_and_hms(2026, 1, 15, 10, 20, 0).unwrap();
+
+        assert_eq!(
+            sampled_hours(&hourly, EVERY_THREE_HOURS, now.with_timezone(&Utc)),
+            [12, 15, 18, 21]
+        );
+    }
+
+    #[test]
+    fn three_hour_utc_steps_take_the_next_step_in_each_slot() {
+        // OpenWeatherMap steps at 00, 03, 06 UTC are 01, 04, 07 in Berlin
+        let berlin = chrono_tz::Europe::Berlin;
+        let first = Utc.with_ymd_and_hms(2026, 1, 15, 0, 0, 0).unwrap();
+        let hourly = steps(first, 3, 16, berlin);
+        let now = berlin.with_ymd_and_hms(2026, 1, 15, 9, 30, 0).unwrap();
+
+        // 10:00 is in the 09-12 slot that already started, so it is skipped
+        assert_eq!(
+            sampled_hours(&hourly, EVERY_THREE_HOURS, now.with_timezone(&Utc)),
+            [13, 16, 19, 22]
+        );
+    }
+
+    #[test]
+    fn six_hour_steps_fill_every_other_slot() {
+        // MET Norway's 6-hour steps at 00, 06, 12, 18 UTC in Oslo summer time
+        let oslo = chrono_tz::Europe::Oslo;
+        let first = Utc.with_ymd_and_hms(2026, 7, 1, 0, 0, 0).unwrap();
+        let hourly = steps(first, 6, 12, oslo);
+        let now = oslo.with_ymd_and_hms(2026, 7, 1, 7, 0, 0).unwrap();
+
+        // Only steps within the 24 hour horizon, one per 3 hour slot
+        assert_eq!(
+            sampled_hours(&hourly, EVERY_THREE_HOURS, now.with_timezone(&Utc)),
+            [14, 20, 2]
+        );
+
+        let six_hourly = HourlySampling {
+            interval_hours: 6,
+            count: 4,
+            horizon_hours: 48,
+        };
+        assert_eq!(
+            sampled_hours(&hourly, six_hourly, now.with_timezone(&Utc)),
+            [14, 20, 2, 8]
+        );
+    }
+
+    #[test]
+    fn steps_coarser_than_the_interval_keep_their_order() {
+        // 6-hour steps at 01, 07, 13, 19 local with a 6 hour interval
+        let berlin = chrono_tz::Europe::Berlin;
+        let first = Utc.with_ymd_and_hms(2026, 1, 15, 0, 0, 0).unwrap();
+        let hourly = steps(first, 6, 8, berlin);
+        let now = berlin.with_ymd_and_hms(2026, 1, 15, 5, 0, 0).unwrap();
+        let sampling = HourlySampling {
+            interval_hours: 6,
+            count: 4,
+            horizon_hours: 24,
+        };
+
+        assert_eq!(
+            sampled_hours(&hourly, sampling, now.with_timezone(&Utc)),
+            [7, 13, 19, 1]
+        );
+    }
+}
@@ -0,0 +1,66 @@
+#let c-card = white
+#let c-muted = rgb("#374151")
+#let c-line  = rgb("#cbd5e1")
+#let c-fill  = rgb("#111827")
+
+#let bold(t, size: 12pt) = text(size, weight: "bold")[#t]
+#let dim(t, size: 12pt) = text(size, fill: c-muted)[#t]
+
+// One segment per level on the tile's scale, filled up to the current one
+#let level-bar(level, levels) = grid(
+  columns: (1fr,) * levels,
+  gutter: 2pt,
+  ..range(levels).map(i => rect(
+    width: 100%,
+    height: 6pt,
+    radius: 2pt,
+    fill: if i <= level { c-fill } else { none },
+    stroke: 1pt + if i <= level { c-fill } else { c-line },
+  )),
+)
+
+#let reading-tile(tile) = rect(
+  width: 100%,
+  fill: c-card,
+  stroke: 1.8pt + c-line,
+  radius: 12pt,
+  inset: (x: 8pt, y: 8pt),
+)[
+  #stack(
+    spacing: 6pt,
+    dim(tile.label, size: 12pt),
+    [#bold(tile.value, size: 20pt)#if "unit" in tile [ #dim(tile.unit, size: 10pt)]],
+    level-bar(tile.level, tile.levels),
+    dim(tile.level_name, size: 11pt),
+  )
+]
+
+#let tile-row(tiles, columns) = grid(
+  columns: (1fr,) * columns,
+  gutter: 6pt,
+  ..tiles.map(reading-tile),
+)
+
+// Pollutants on top, pollen below. Pollen is only modelled for Europe, so
+// elsewhere that row is a note.
+#let render-air-quality(data) = rect(
+  width: 100%,
+  fill: c-card,
+  stroke: none,
+  radius: 22pt,
+  inset: 16pt,
+)[
+  #if not data.available or data.pollutants.len() == 0 {
+    align(center, dim(data.labels.unavailable, size: 16pt))
+  } else {
+    stack(
+      spacing: 8pt,
+      tile-row(data.pollutants, 5),
+      if data.pollen.len() > 0 {
+        tile-row(data.pollen, 5)
+      } else {
+        align(center, dim(data.labels.no_pollen, size: 12pt))
+      },
+    )
+  }
+]
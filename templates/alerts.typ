@@ -0,0 +1,41 @@
+// Banner look per CAP severity: severe and extreme alerts are inverted and
+// larger so they read from across the room
+#let banner-styles = (
+  extreme: (fill: black, ink: white, stroke: none, size: 26pt, inset: 16pt),
+  severe: (fill: black, ink: white, stroke: none, size: 22pt, inset: 14pt),
+  moderate: (fill: white, ink: black, stroke: 3pt + black, size: 18pt, inset: 12pt),
+  minor: (fill: white, ink: black, stroke: 2pt + black, size: 16pt, inset: 10pt),
+  unknown: (fill: white, ink: black, stroke: 2pt + black, size: 16pt, inset: 10pt),
+)
+
+#let alert-banner(alert) = {
+  let style = banner-styles.at(alert.severity, default: banner-styles.unknown)
+  rect(
+    width: 100%,
+    fill: style.fill,
+    stroke: style.stroke,
+    radius: 14pt,
+    inset: style.inset,
+  )[
+    #set text(fill: style.ink)
+    #grid(
+      columns: (auto, 1fr, auto),
+      gutter: 10pt,
+      align: (center + horizon, left + horizon, right + horizon),
+      text(style.size * 1.4)[⚠],
+      stack(
+        spacing: 6pt,
+        text(style.size, weight: "bold")[#alert.event],
+        text(style.size * 0.6)[#alert.headline],
+      ),
+      if "expires" in alert {
+        text(style.size * 0.7, weight: "bold")[#alert.expires]
+      },
+    )
+  ]
+}
+
+#let render-alerts(data) = stack(
+  spacing: 8pt,
+  ..data.alerts.map(alert-banner),
+)
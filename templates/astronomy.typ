@@ -0,0 +1,75 @@
+#import "icons.typ": glyph, moon-icon
+
+#let c-card = white
+#let c-muted = rgb("#374151")
+#let c-line  = rgb("#cbd5e1")
+
+#let bold(t, size: 12pt) = text(size, weight: "bold")[#t]
+#let dim(t, size: 12pt) = text(size, fill: c-muted)[#t]
+
+#let event(label, time) = stack(
+  spacing: 4pt,
+  dim(label, size: 12pt),
+  bold(time, size: 18pt),
+)
+
+// Below this width the four blocks don't fit side by side.
+#let narrow-width = 440pt
+
+// Sun, day length, moon phase and moon times side by side, or as a sun row
+// and a moon row when narrow. Times are "--" on days without the event, e.g.
+// polar night or a moon that doesn't rise.
+#let render-astronomy(data) = rect(
+  width: 100%,
+  fill: c-card,
+  stroke: none,
+  radius: 22pt,
+  inset: 16pt,
+)[
+  #let sun = stack(
+    spacing: 10pt,
+    event([#glyph("sunrise", size: 14pt) #data.labels.sunrise], data.sunrise),
+    event([#glyph("sunset", size: 14pt) #data.labels.sunset], data.sunset),
+  )
+  #let daylight = stack(
+    spacing: 8pt,
+    event(data.labels.daylight, data.daylight),
+    dim(data.daylight_change, size: 14pt),
+  )
+  #let moon = stack(
+    spacing: 6pt,
+    moon-icon(data.moon, 44pt, southern: data.southern),
+    bold(data.moon.illumination, size: 14pt),
+    dim(data.moon.name, size: 12pt),
+  )
+  #let moon-times = stack(
+    spacing: 10pt,
+    event(data.labels.moonrise, data.moon.moonrise),
+    event(data.labels.moonset, data.moon.moonset),
+  )
+
+  #layout(size => if size.width < narrow-width {
+    grid(
+      columns: (1fr, 1fr),
+      gutter: 8pt,
+      row-gutter: 14pt,
+      align: center + horizon,
+      sun, daylight,
+      grid.hline(stroke: 1pt + c-line),
+      moon, moon-times,
+    )
+  } else {
+    grid(
+      columns: (1fr, auto, 1fr, 1fr),
+      gutter: 8pt,
+      align: center + horizon,
+      sun, daylight, moon, moon-times,
+    )
+  })
+
+  #v(4pt)
+  #align(center, dim(
+    [#data.labels.twilight #data.civil_dawn – #data.civil_dusk],
+    size: 12pt,
+  ))
+]
@@ -0,0 +1,94 @@
+#let c-card = white
+#let c-muted = rgb("#374151")
+#let c-line  = rgb("#cbd5e1")
+// Mid gray that survives quantization to 16 levels without dithering noise
+#let c-bar   = luma(187)
+
+#let bold(t, size: 12pt) = text(size, weight: "bold")[#t]
+#let dim(t, size: 12pt) = text(size, fill: c-muted)[#t]
+
+#let plot-height = 170pt
+#let axis-width = 40pt
+#let header-height = 22pt
+#let footer-height = 24pt
+
+// Places `body` centered horizontally and vertically on (x, y).
+#let centered-at(x, y, width: 60pt, height: 16pt, body) = place(
+  top + left,
+  dx: x - width / 2,
+  dy: y - height / 2,
+  box(width: width, height: height, align(center + horizon, body)),
+)
+
+#let plot(data, width) = {
+  let axis = data.temperature_axis
+  let plot-width = width - 2 * axis-width
+  let x-pos(x) = axis-width + plot-width * (x / data.span)
+  let y-temp(t) = header-height + plot-height * (1 - (t - axis.min) / (axis.max - axis.min))
+  let y-rain(p) = header-height + plot-height * (1 - p / 100)
+  let slot = plot-width / data.span
+
+  box(width: width, height: header-height + plot-height + footer-height, {
+    // Temperature gridlines and left axis
+    for t in range(axis.min, axis.max + 1, step: axis.step) {
+      place(top + left, dx: axis-width, dy: y-temp(t), line(length: plot-width, stroke: 1pt + c-line))
+      centered-at(axis-width / 2, y-temp(t), width: axis-width, dim([#t°], size: 13pt))
+    }
+    centered-at(axis-width / 2, header-height / 2, width: axis-width, bold(data.labels.temperature, size: 13pt))
+
+    // Precipitation probability axis on the right
+    for p in (0, 50, 100) {
+      centered-at(width - axis-width / 2, y-rain(p), width: axis-width, dim([#p], size: 13pt))
+    }
+    centered-at(width - axis-width / 2, header-height / 2, width: axis-width, bold([☔ #data.labels.precipitation], size: 13pt))
+
+    // Precipitation bars, one per hour, clipped to the plot area
+    place(top + left, dx: axis-width, dy: header-height, box(width: plot-width, height: plot-height, clip: true, {
+      for point in data.points.filter(point => point.precipitation != none and point.precipitation > 0) {
+        place(
+          top + left,
+          dx: plot-width * (point.x / data.span) - slot * 0.35,
+          dy: y-rain(point.precipitation) - header-height,
+          rect(width: slot * 0.7, height: plot-height * point.precipitation / 100, fill: c-bar),
+        )
+      }
+    }))
+
+    // Temperature line
+    let (first, ..rest) = data.points
+    place(top + left, curve(
+      stroke: (paint: black, thickness: 3pt, join: "round", cap: "round"),
+      curve.move((x-pos(first.x), y-temp(first.temperature))),
+      ..rest.map(point => curve.line((x-pos(point.x), y-temp(point.temperature)))),
+    ))
+
+    // "Now" marker
+    place(
+      top + left,
+      dx: x-pos(data.now),
+      dy: header-height,
+      line(angle: 90deg, length: plot-height, stroke: (paint: black, thickness: 2pt, dash: "dashed")),
+    )
+    centered-at(x-pos(data.now), header-height / 2, width: 80pt, bold(data.labels.now, size: 13pt))
+
+    // Time axis
+    place(top + left, dx: axis-width, dy: header-height + plot-height, line(length: plot-width, stroke: 1.5pt + c-muted))
+    for tick in data.ticks {
+      centered-at(x-pos(tick.x), header-height + plot-height + footer-height / 2, width: 70pt, dim(tick.label, size: 13pt))
+    }
+  })
+}
+
+#let render-chart(data) = rect(
+  width: 100%,
+  fill: c-card,
+  stroke: none,
+  radius: 22pt,
+  inset: 16pt,
+)[
+  #if data.points.len() < 2 {
+    align(center, dim(data.labels.unavailable, size: 16pt))
+  } else {
+    layout(size => plot(data, size.width))
+  }
+]
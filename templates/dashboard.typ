@@ -1,5 +1,4 @@
-#import "weather.typ": render-weather
-#import "wanikani.typ": render-wanikani
+#import "alerts.typ": render-alerts
 
 #set page(
   width: sys.inputs.width * 1pt,
@@ -14,11 +13,43 @@
   fill: rgb("#111827"),
 )
 
-#let weather-data = sys.inputs.weather-data
-#let wanikani-data = sys.inputs.wanikani-data
+// Tag straddling the top edge of widgets drawn without (some of) their data,
+// clear of their own content
+#let unavailable-tag(label) = rect(
+  fill: white,
+  stroke: 1.5pt + black,
+  radius: 8pt,
+  inset: (x: 8pt, y: 5pt),
+)[#text(11pt, weight: "bold")[⚠ #label]]
+
+// Each widget comes with the module defining its `render-<key>` function. The
+// `<slot-end>` metadata after it tells the server which widgets didn't fit on
+// the page.
+#let render(widget) = {
+  import widget.template as module
+  let body = dictionary(module).at("render-" + widget.key)(widget.data)
+  if "unavailable" in widget {
+    block(width: 100%, {
+      body
+      place(top + right, dx: -20pt, dy: -11pt, unavailable-tag(widget.unavailable))
+    })
+  } else {
+    body
+  }
+  [#metadata(widget.key) <slot-end>]
+}
+
+// Active alerts sit above every widget
+#let alerts = sys.inputs.alerts-data.alerts
+#let widgets = sys.inputs.layout.widgets
 
 #stack(
   spacing: 18pt,
-  render-weather(weather-data),
-  render-wanikani(wanikani-data),
+  ..if alerts.len() > 0 { (render-alerts(sys.inputs.alerts-data),) },
+  grid(
+    columns: (1fr,) * sys.inputs.layout.columns,
+    column-gutter: 12pt,
+    row-gutter: 18pt,
+    ..widgets.map(widget => grid.cell(colspan: widget.span, render(widget))),
+  ),
 )
@@ -0,0 +1,141 @@
+// Weather icons in either theme: monochrome SVGs from `icons/` (default) or
+// Noto Emoji glyphs, picked by the `icon-theme` input
+#let theme = sys.inputs.at("icon-theme", default: "svg")
+
+// Keyed by `WeatherCondition::icon_key`; keys missing here fall back to a
+// shorter one, e.g. `rain-showers-night-heavy` to `rain-showers-night`
+#let condition-emoji = (
+  "clear-day": "☀️",
+  "clear-night": "🌙",
+  "mainly-clear-day": "🌤️",
+  "mainly-clear-night": "🌙",
+  "partly-cloudy-day": "⛅",
+  "partly-cloudy-night": "☁️",
+  "overcast": "☁️",
+  "fog": "🌫️",
+  "rime-fog": "🌫️",
+  "drizzle": "🌦️",
+  "freezing-drizzle": "🌧️❄️",
+  "rain": "🌧️",
+  "rain-heavy": "🌧️💧",
+  "freezing-rain": "🌧️🧊",
+  "snow": "🌨️",
+  "snow-heavy": "🌨️❄️",
+  "snow-grains": "❄️",
+  "rain-showers-day": "🌦️",
+  "rain-showers-night": "🌧️",
+  "snow-showers": "🌨️",
+  "thunderstorm": "⛈️",
+  "thunderstorm-hail": "⛈️🧊",
+  "unknown": "❓",
+)
+
+// Files in `icons/`, without the extension
+#let condition-svg = (
+  "clear-day",
+  "clear-night",
+  "mainly-clear-day",
+  "mainly-clear-night",
+  "partly-cloudy-day",
+  "partly-cloudy-night",
+  "overcast",
+  "fog",
+  "rime-fog",
+  "drizzle",
+  "drizzle-light",
+  "freezing-drizzle",
+  "rain",
+  "rain-light",
+  "rain-heavy",
+  "freezing-rain",
+  "snow",
+  "snow-light",
+  "snow-heavy",
+  "snow-grains",
+  "rain-showers-day",
+  "rain-showers-night",
+  "snow-showers-day",
+  "snow-showers-night",
+  "thunderstorm",
+  "thunderstorm-hail",
+  "unknown",
+)
+
+// Label glyphs that aren't weather conditions
+#let glyph-emoji = (
+  "humidity": "💧",
+  "umbrella": "☔",
+  "wind": "💨",
+  "gusts": "🌬️",
+  "pressure": "⏲️",
+  "uv": "🔆",
+  "visibility": "👁️",
+  "dew-point": "🌡️",
+  "cloud-cover": "☁️",
+  "arrow": "⬆️",
+  "sunrise": "🌅",
+  "sunset": "🌇",
+)
+
+#let resolve(key, known) = {
+  let parts = key.split("-")
+  while parts.len() > 0 {
+    let candidate = parts.join("-")
+    if candidate in known {
+      return candidate
+    }
+    let _ = parts.pop()
+  }
+  "unknown"
+}
+
+#let svg-icon(name, size) = box(
+  height: size,
+  baseline: 15%,
+  image("icons/" + name + ".svg", height: size),
+)
+
+#let emoji(e, size) = text(font: "Noto Emoji", size: size)[#e]
+
+// Large condition icon, e.g. `condition-icon("clear-night", 96pt)`. Emoji
+// glyphs overshoot their font size, so the SVGs are scaled up to match.
+#let condition-icon(key, size) = if theme == "emoji" {
+  emoji(condition-emoji.at(resolve(key, condition-emoji)), size)
+} else {
+  svg-icon(resolve(key, condition-svg), size * 1.25)
+}
+
+// Inline glyph sized to the surrounding text, e.g. `glyph("umbrella")`
+#let glyph(name, size: 1em) = if theme == "emoji" {
+  emoji(glyph-emoji.at(name), size)
+} else {
+  svg-icon(name, size)
+}
+
+#let moon-emoji = ("🌑", "🌒", "🌓", "🌔", "🌕", "🌖", "🌗", "🌘")
+
+// Moon disc lit to match `moon.phase` (0 new, 0.5 full) rather than one of
+// the eight named phases. The northern view is lit from the right while
+// waxing; `southern` mirrors it. The emoji is shrunk to stay inside `size`.
+#let moon-icon(moon, size, southern: false) = if theme == "emoji" {
+  emoji(moon-emoji.at(moon.index), size * 0.8)
+} else {
+  let waxing = moon.phase < 0.5
+  let crescent = moon.phase < 0.25 or moon.phase > 0.75
+  let rx = calc.round(calc.abs(calc.cos(moon.phase * 360deg)) * 30, digits: 2)
+  let outer = if waxing { 1 } else { 0 }
+  let inner = if crescent { 1 - outer } else { outer }
+  let flip = if southern { " transform=\"scale(-1 1)\"" } else { "" }
+  let svg = (
+    "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"-32 -32 64 64\">"
+      + "<circle r=\"30\" fill=\"#444\" stroke=\"#000\" stroke-width=\"2\"/>"
+      + "<path" + flip + " fill=\"#fff\" d=\"M0 -30 A30 30 0 0 " + str(outer)
+      + " 0 30 A" + str(rx) + " 30 0 0 " + str(inner) + " 0 -30 Z\"/>"
+      + "</svg>"
+  )
+  box(
+    height: size,
+    baseline: 15%,
+    image(bytes(svg), format: "svg", height: size),
+  )
+}
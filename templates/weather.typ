@@ -1,7 +1,4 @@
-#let emoji(e, size: 48pt) = text(
-  font: "Noto Emoji",
-  size: size,
-)[#e]
+#import "icons.typ": condition-icon, glyph
 
 #let c-card = white
 #let c-muted = rgb("#374151")
@@ -10,30 +7,6 @@
 #let bold(t, size: 12pt) = text(size, weight: "bold")[#t]
 #let dim(t, size: 12pt) = text(size, fill: c-muted)[#t]
 
-#let condition-emoji = (
-  "Clear sky": "☀️",
-  "Mostly clear": "🌤️",
-  "Overcast": "☁️",
-  "Fog": "🌫️",
-  "Drizzle": "🌦️",
-  "Freezing drizzle": "🌧️❄️",
-  "Rain": "🌧️",
-  "Freezing rain": "🌧️🧊",
-  "Snowfall": "🌨️",
-  "Snow grains": "❄️",
-  "Rain showers": "🌦️",
-  "Snow showers": "🌨️",
-  "Thunderstorm": "⛈️",
-  "Thunderstorm with hail": "⛈️🧊",
-  "Unknown": "❓",
-)
-
-#let condition-icon(c) = if condition-emoji.keys().contains(c) {
-  condition-emoji.at(c)
-} else {
-  condition-emoji.at("Unknown")
-}
-
 #let hour-col(time, temp, rain) = rect(
   fill: c-card,
   stroke: 1.8pt + c-line,
@@ -44,10 +17,56 @@
     spacing: 6pt,
     text(size: 16pt)[#time],
     bold(temp, size: 24pt),
-    dim([☔ *#rain*], size: 18pt),
+    dim([#glyph("umbrella") *#rain*], size: 18pt),
   )
 ]
 
+#let trend-arrow = (rising: "↑", falling: "↓", steady: "→")
+
+// Wind chips show a compass arrow pointing where the wind blows to,
+// opposite the bearing it comes from
+#let metric-chip(chip) = rect(
+  width: 100%,
+  fill: c-card,
+  stroke: 1.8pt + c-line,
+  radius: 12pt,
+  inset: (x: 8pt, y: 8pt),
+)[
+  #stack(
+    spacing: 6pt,
+    dim(size: 12pt)[
+      #if "direction" in chip {
+        box(rotate((chip.direction + 180) * 1deg, glyph("arrow", size: 14pt)))
+      } else {
+        glyph(chip.icon, size: 14pt)
+      }
+      #chip.label
+      #if "detail" in chip [*#chip.detail*]
+      #if "trend" in chip [*#trend-arrow.at(chip.trend)*]
+    ],
+    [#bold(chip.value, size: 18pt)#if "unit" in chip [#h(2pt)#dim(chip.unit, size: 11pt)]],
+  )
+]
+
+// One bar per 15 minutes, bottom-aligned; dry steps keep a thin baseline
+// so the strip still reads as a timeline
+#let nowcast-row(nowcast) = grid(
+  columns: (1fr, auto),
+  gutter: 10pt,
+  align: (left + horizon, bottom),
+  dim([#glyph("umbrella", size: 18pt) *#nowcast.summary*], size: 18pt),
+  stack(
+    dir: ltr,
+    spacing: 2pt,
+    ..nowcast.bars.map(bar => rect(
+      width: 10pt,
+      height: calc.max(bar * 28pt, 2pt),
+      fill: black,
+      stroke: none,
+    )),
+  ),
+)
+
 #let render-weather(data) = rect(
   fill: c-card,
   stroke: none,
@@ -61,7 +80,10 @@
     stack(
       spacing: 8pt,
       bold(data.day, size: 32pt),
-      dim(data.datetime, size: 16pt),
+      dim(
+        if "location" in data [*#data.location* · #data.datetime] else { data.datetime },
+        size: 16pt,
+      ),
     ),
     stack(
       spacing: 6pt,
@@ -73,29 +95,105 @@
   #v(10pt)
 
   #grid(
-    columns: (auto, 30%, auto),
+    columns: (auto, 1fr, auto),
     gutter: 10pt,
     align: center,
     stack(
       spacing: 16pt,
-      emoji(condition-icon(data.condition), size: 96pt),
+      condition-icon(data.icon, 96pt),
       dim(data.condition, size: 18pt),
     ),
     [],
     stack(
       spacing: 4pt,
-      dim([Hum *#data.humidity*], size: 24pt),
+      dim([#glyph("humidity") #data.labels.humidity *#data.humidity*], size: 24pt),
       bold(data.temperature, size: 90pt),
-      dim([Feels *#data.real_feel*], size: 24pt),
+      dim([#data.labels.feels_like *#data.real_feel*], size: 24pt),
     ),
   )
 
+  #if "comparison" in data {
+    v(6pt)
+    align(right, dim(data.comparison.join("  ·  "), size: 16pt))
+  }
+
+  #if data.metrics.len() > 0 {
+    v(10pt)
+    grid(
+      columns: (1fr,) * 4,
+      gutter: 4pt,
+      ..data.metrics.map(metric-chip)
+    )
+  }
+
+  #if "nowcast" in data {
+    v(10pt)
+    nowcast-row(data.nowcast)
+  }
+
   #v(10pt)
 
   #grid(
-    columns: (1fr, 1fr, 1fr, 1fr),
+    columns: (1fr,) * data.hours.len(),
     align: center,
     gutter: 4pt,
     ..data.hours.map(h => hour-col(h.time, h.temperature, h.rain))
   )
 ]
+
+#let day-col(day) = stack(
+  spacing: 6pt,
+  bold(day.day, size: 18pt),
+  condition-icon(day.icon, 32pt),
+  [#bold(day.high, size: 18pt) #dim(day.low, size: 16pt)],
+  dim([#glyph("umbrella") *#day.rain*], size: 14pt),
+  dim(day.precipitation, size: 13pt),
+  dim([#glyph("sunrise") #day.sunrise], size: 12pt),
+  dim([#glyph("sunset") #day.sunset], size: 12pt),
+)
+
+#let render-daily(data) = rect(
+  width: 100%,
+  fill: c-card,
+  stroke: none,
+  radius: 22pt,
+  inset: 16pt,
+)[
+  #if data.days.len() == 0 {
+    align(center, dim(data.unavailable, size: 16pt))
+  } else {
+    grid(
+      columns: (1fr,) * data.days.len(),
+      align: center,
+      gutter: 4pt,
+      ..data.days.map(day-col)
+    )
+  }
+]
+
+#let location-card(location) = rect(
+  width: 100%,
+  fill: c-card,
+  stroke: none,
+  radius: 18pt,
+  inset: (x: 12pt, y: 10pt),
+)[
+  #stack(
+    spacing: 6pt,
+    bold(location.name, size: 16pt),
+    grid(
+      columns: (auto, 1fr),
+      gutter: 8pt,
+      align: (left + horizon, right + horizon),
+      condition-icon(location.icon, 40pt),
+      bold(location.temperature, size: 30pt),
+    ),
+    align(right)[#bold(location.high, size: 16pt) #dim(location.low, size: 14pt)],
+  )
+]
+
+#let render-locations(data) = grid(
+  columns: (1fr,) * calc.max(data.locations.len(), 1),
+  gutter: 10pt,
+  ..data.locations.map(location-card)
+)